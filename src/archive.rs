@@ -0,0 +1,184 @@
+use std::{
+    collections::HashMap,
+    fs,
+    io::{
+        self,
+        ErrorKind,
+    },
+    path::Path,
+};
+
+use getset::{
+    CopyGetters,
+    Getters,
+};
+use uuid::Uuid;
+
+use crate::game::History;
+
+/// Every recorded game keyed by a random id.
+pub type Archive = HashMap<Uuid, History>;
+
+/// Path of the archive the game writes to.
+pub static DEFAULT_PATH: &str = "history.json";
+
+/// Reads the archive at `path`. A missing file is treated as an empty archive.
+pub fn load<P: AsRef<Path>>(path: P) -> Result<Archive, io::Error>
+{
+    match fs::read_to_string(path) {
+        Ok(data) => serde_json::from_str(&data)
+            .map_err(|error| io::Error::new(ErrorKind::InvalidData, error)),
+        Err(error) if matches!(error.kind(), ErrorKind::NotFound) => Ok(Archive::new()),
+        Err(error) => Err(error),
+    }
+}
+
+/// Writes `archive` to `path`, replacing whatever was there.
+pub fn save<P: AsRef<Path>>(path: P, archive: &Archive) -> Result<(), io::Error>
+{
+    let data = serde_json::to_string(archive)
+        .map_err(|error| io::Error::new(ErrorKind::InvalidData, error))?;
+    fs::write(path, data)
+}
+
+/// Adds `history` to the archive at `path` under a new id.
+pub fn append<P: AsRef<Path>>(path: P, history: History) -> Result<(), io::Error>
+{
+    let mut archive = load(&path)?;
+    archive.insert(Uuid::new_v4(), history);
+    save(&path, &archive)
+}
+
+/// Checks every game in `archive` with `History::validate`. The error names the first game that
+/// can not be replayed.
+pub fn validate(archive: &Archive) -> Result<(), io::Error>
+{
+    for (id, history) in archive {
+        history.validate().map_err(|error| {
+            io::Error::new(ErrorKind::InvalidData, format!("game {}: {}", id, error))
+        })?;
+    }
+    Ok(())
+}
+
+/// What happened to the games when archives were merged.
+#[derive(Debug, Default, CopyGetters, Getters)]
+pub struct MergeReport
+{
+    #[getset(get_copy = "pub")]
+    added: usize,
+
+    /// Games skipped since they were already merged under the same id.
+    #[getset(get_copy = "pub")]
+    duplicate_ids: usize,
+
+    /// Games skipped since they were already merged under another id.
+    #[getset(get_copy = "pub")]
+    duplicate_contents: usize,
+
+    /// Ids used for different games in different archives. The first game keeps the id and the
+    /// other is added under the new id paired with it.
+    #[getset(get = "pub")]
+    conflicts: Vec<(Uuid, Uuid)>,
+}
+
+/// Combines `archives` into one, skipping games that have already been added. Archives earlier in
+/// the order take precedence when the same id is used for different games.
+pub fn merge<I: IntoIterator<Item = Archive>>(archives: I) -> (Archive, MergeReport)
+{
+    let mut merged = Archive::new();
+    let mut report = MergeReport::default();
+
+    // The ids of the merged games by start date and amount of events, so only games that can be
+    // equal are compared when looking for duplicates.
+    let mut index: HashMap<_, Vec<Uuid>> = HashMap::new();
+
+    for archive in archives {
+        for (id, history) in archive {
+            let key = (*history.start_date(), history.events().len());
+            let duplicate = index
+                .get(&key)
+                .is_some_and(|ids| ids.iter().any(|other| merged[other] == history));
+
+            let id = match merged.get(&id) {
+                Some(other) if other == &history => {
+                    report.duplicate_ids += 1;
+                    continue;
+                }
+                _ if duplicate => {
+                    report.duplicate_contents += 1;
+                    continue;
+                }
+                Some(_) => {
+                    let new_id = Uuid::new_v4();
+                    report.conflicts.push((id, new_id));
+                    new_id
+                }
+                None => id,
+            };
+
+            merged.insert(id, history);
+            index.entry(key).or_default().push(id);
+            report.added += 1;
+        }
+    }
+
+    (merged, report)
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::game::{
+        Event,
+        GameResult,
+    };
+
+    fn history(cards_left: usize) -> History
+    {
+        History::ended(&[Event::Eliminate(0)], Some(GameResult::Lose), cards_left)
+    }
+
+    #[test]
+    fn merge_removes_duplicates_and_reports_conflicts()
+    {
+        let (a, b, c) = (history(10), history(20), history(30));
+        let (id_a, id_b) = (Uuid::new_v4(), Uuid::new_v4());
+
+        let laptop: Archive = vec![(id_a, a.clone()), (id_b, b.clone())]
+            .into_iter()
+            .collect();
+        let desktop: Archive = vec![(id_a, a), (Uuid::new_v4(), b), (id_b, c.clone())]
+            .into_iter()
+            .collect();
+
+        let (merged, report) = merge(vec![laptop, desktop]);
+
+        assert_eq!(merged.len(), 3);
+        assert_eq!(report.added(), 3);
+        assert_eq!(report.duplicate_ids(), 1);
+        assert_eq!(report.duplicate_contents(), 1);
+        assert_eq!(report.conflicts().len(), 1);
+
+        let (id, new_id) = report.conflicts()[0];
+        assert_eq!(id, id_b);
+        assert_eq!(merged[&new_id], c);
+    }
+
+    #[test]
+    fn validate_names_invalid_game()
+    {
+        let id = Uuid::new_v4();
+        let mut archive: Archive = vec![(Uuid::new_v4(), history(10))].into_iter().collect();
+        assert!(validate(&archive).is_ok());
+
+        archive.insert(
+            id,
+            History::ended(&[Event::Eliminate(7)], Some(GameResult::Lose), 10),
+        );
+        let error = validate(&archive).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        assert!(error.to_string().contains(&id.to_string()));
+    }
+}
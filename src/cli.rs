@@ -0,0 +1,181 @@
+use std::{
+    error::Error,
+    path::Path,
+};
+
+use uuid::Uuid;
+
+use crate::{
+    archive,
+    export::{
+        self,
+        format_duration,
+    },
+    game::{
+        analytics::Timings,
+        History,
+    },
+    statistics::{
+        format_average,
+        format_rate,
+        Statistics,
+    },
+};
+
+static USAGE: &str =
+    "usage: the-idiot [stats | timings [GAME-ID] | export (csv | markdown) | import ARCHIVE...]";
+
+/// Runs the command in `args`, the arguments given to the program without the program name, on the
+/// archive at `archive_path`.
+pub fn run(args: &[String], archive_path: &Path) -> Result<(), Box<dyn Error>>
+{
+    match args.split_first() {
+        Some((command, [])) if command == "stats" => stats(archive_path),
+        Some((command, args)) if command == "timings" => timings(args, archive_path),
+        Some((command, [format])) if command == "export" => export(format, archive_path),
+        Some((command, paths)) if command == "import" && !paths.is_empty() => {
+            import(paths, archive_path)
+        }
+        _ => Err(USAGE.into()),
+    }
+}
+
+/// Prints the archive in the given format.
+fn export(format: &str, archive_path: &Path) -> Result<(), Box<dyn Error>>
+{
+    let archive = archive::load(archive_path)?;
+
+    match format {
+        "csv" => print!("{}", export::csv(&archive)),
+        "markdown" => print!("{}", export::markdown(&archive)),
+        _ => return Err(format!("unknown export format '{}'", format).into()),
+    }
+    Ok(())
+}
+
+/// Merges the archives at `paths` into the archive of this machine.
+fn import(paths: &[String], archive_path: &Path) -> Result<(), Box<dyn Error>>
+{
+    let mut archives = vec![archive::load(archive_path)?];
+    for path in paths {
+        if !Path::new(path).exists() {
+            return Err(format!("{}: no such file", path).into());
+        }
+        // A game that can not be replayed would break the statistics of the merged archive.
+        let archive = archive::load(path)
+            .and_then(|archive| archive::validate(&archive).map(|()| archive))
+            .map_err(|error| format!("{}: {}", path, error))?;
+        archives.push(archive);
+    }
+
+    let (merged, report) = archive::merge(archives);
+    archive::save(archive_path, &merged)?;
+
+    println!("Games in archive: {}", merged.len());
+    println!("Skipped duplicate ids: {}", report.duplicate_ids());
+    println!("Skipped duplicate games: {}", report.duplicate_contents());
+    for (id, new_id) in report.conflicts() {
+        println!(
+            "Conflict: {} was used for different games, the imported one is stored as {}",
+            id, new_id
+        );
+    }
+    Ok(())
+}
+
+/// Prints the lifetime statistics of every game in the archive.
+fn stats(archive_path: &Path) -> Result<(), Box<dyn Error>>
+{
+    let archive = archive::load(archive_path)?;
+    let statistics = Statistics::new(archive.values());
+
+    println!(
+        "Games played: {} ({} won, {} lost, {} abandoned)",
+        statistics.games_played(),
+        statistics.wins(),
+        statistics.losses(),
+        statistics.abandoned()
+    );
+    println!("Win rate: {}", format_rate(statistics.win_rate()));
+    println!("Current streak: {}", statistics.current_streak());
+    println!("Best streak: {}", statistics.best_streak());
+    println!(
+        "Average cards left: {}",
+        format_average(statistics.average_cards_left())
+    );
+
+    println!("Final table sizes:");
+    for (size, count) in statistics.table_sizes() {
+        println!(
+            "  {:>2}: {:<20} {}",
+            size,
+            "#".repeat((*count).min(20)),
+            count
+        );
+    }
+
+    println!("Weekly trend:");
+    for ((year, week), trend) in statistics.weeks() {
+        println!(
+            "  {}-W{:02}: {} games, {} won, {} cards left on average",
+            year,
+            week,
+            trend.games_played(),
+            format_rate(trend.win_rate()),
+            format_average(trend.average_cards_left())
+        );
+    }
+
+    Ok(())
+}
+
+/// Prints the thinking time analytics of the game with the id in `args`, or of the last game if
+/// no id is given.
+fn timings(args: &[String], archive_path: &Path) -> Result<(), Box<dyn Error>>
+{
+    let archive = archive::load(archive_path)?;
+
+    let (id, history) = match args.first() {
+        Some(id) => {
+            let id = Uuid::parse_str(id)?;
+            archive
+                .get_key_value(&id)
+                .ok_or_else(|| format!("no game with id {}", id))?
+        }
+        None => archive
+            .iter()
+            .max_by_key(|(_, history)| history.start_date())
+            .ok_or("the archive is empty")?,
+    };
+
+    let timings = Timings::new(history).ok_or("the game was recorded without timestamps")?;
+
+    print_game(id, history);
+    println!("Moves: {}", timings.events().len());
+    println!("Total time: {}", format_duration(timings.total()));
+
+    println!("Time per deal:");
+    for (index, time) in timings.deal_times().into_iter().enumerate() {
+        println!("  {:>2}: {}", index + 1, format_duration(time));
+    }
+
+    println!("Longest pauses:");
+    for (index, time) in timings.longest_pauses(5) {
+        println!(
+            "  move {:>3} ({}): {}",
+            index + 1,
+            timings.events()[index],
+            format_duration(time)
+        );
+    }
+
+    Ok(())
+}
+
+fn print_game(id: &Uuid, history: &History)
+{
+    match history.start_date() {
+        Some(date) => println!("Game {} started {}", id, date.format("%Y-%m-%d %H:%M")),
+        None => println!("Game {}", id),
+    }
+}
@@ -0,0 +1,9 @@
+pub mod keymap;
+pub mod settings;
+pub mod theme;
+
+pub use keymap::{
+    Action,
+    Keymap,
+};
+pub use settings::Settings;
@@ -0,0 +1,488 @@
+use std::{
+    collections::{
+        BTreeMap,
+        HashMap,
+    },
+    error::Error,
+    fmt::{
+        self,
+        Display,
+    },
+    fs,
+    io::{
+        self,
+        ErrorKind,
+    },
+    path::Path,
+    str::FromStr,
+};
+
+use cursive::event::{
+    Event,
+    Key,
+};
+use getset::Getters;
+use serde::Deserialize;
+
+use crate::game::RowIndex;
+
+/// Path of the keymap file that is read at startup.
+pub static DEFAULT_PATH: &str = "keymap.toml";
+
+/// Keys that are taken by the application and can not be bound. The focused widgets handle Enter,
+/// Tab and the arrow keys before any binding would see them.
+static RESERVED_KEYS: [&str; 7] = ["?", "enter", "tab", "left", "right", "up", "down"];
+
+/// Something the player can do by hitting a key.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Action
+{
+    Deal,
+    Place,
+    EliminateRow(RowIndex),
+    NewGame,
+    Undo,
+    Hint,
+    Stats,
+    History,
+    Settings,
+    Autopilot,
+    ShowRow,
+    Help,
+    Quit,
+    FocusLeft,
+    FocusRight,
+    FocusUp,
+    FocusDown,
+}
+
+impl Display for Action
+{
+    /// Writes the name the action has in the keymap file.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        use Action::*;
+
+        match self {
+            Deal => write!(f, "deal"),
+            Place => write!(f, "place"),
+            EliminateRow(index) => write!(f, "eliminate-row-{}", index + 1),
+            NewGame => write!(f, "new-game"),
+            Undo => write!(f, "undo"),
+            Hint => write!(f, "hint"),
+            Stats => write!(f, "stats"),
+            History => write!(f, "history"),
+            Settings => write!(f, "settings"),
+            Autopilot => write!(f, "autopilot"),
+            ShowRow => write!(f, "show-row"),
+            Help => write!(f, "help"),
+            Quit => write!(f, "quit"),
+            FocusLeft => write!(f, "focus-left"),
+            FocusRight => write!(f, "focus-right"),
+            FocusUp => write!(f, "focus-up"),
+            FocusDown => write!(f, "focus-down"),
+        }
+    }
+}
+
+impl Action
+{
+    /// What the action does, as told to the player.
+    pub fn description(&self) -> String
+    {
+        use Action::*;
+
+        match self {
+            Deal => String::from("Deal a card to every row"),
+            Place => String::from("Place a card in an empty row"),
+            EliminateRow(index) => format!(
+                "Select row {} (eliminate, or choose a row while placing)",
+                index + 1
+            ),
+            NewGame => String::from("Start a new game"),
+            Undo => String::from("Undo the last move"),
+            Hint => String::from("Show a hint"),
+            Stats => String::from("Show statistics"),
+            History => String::from("Browse and replay recorded games"),
+            Settings => String::from("Change the settings"),
+            Autopilot => String::from("Let the computer play, or take over again"),
+            ShowRow => String::from("Show or close every card of the focused row"),
+            Help => String::from("Show this help"),
+            Quit => String::from("Quit"),
+            FocusLeft => String::from("Move the focus left"),
+            FocusRight => String::from("Move the focus right"),
+            FocusUp => String::from("Move the focus up"),
+            FocusDown => String::from("Move the focus down"),
+        }
+    }
+}
+
+impl FromStr for Action
+{
+    type Err = KeymapError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err>
+    {
+        use Action::*;
+
+        let row = name
+            .strip_prefix("eliminate-row-")
+            .and_then(|row| row.parse::<RowIndex>().ok())
+            .filter(|row| (1..=4).contains(row));
+
+        match (name, row) {
+            (_, Some(row)) => Ok(EliminateRow(row - 1)),
+            ("deal", _) => Ok(Deal),
+            ("place", _) => Ok(Place),
+            ("new-game", _) => Ok(NewGame),
+            ("undo", _) => Ok(Undo),
+            ("hint", _) => Ok(Hint),
+            ("stats", _) => Ok(Stats),
+            ("history", _) => Ok(History),
+            ("settings", _) => Ok(Settings),
+            ("autopilot", _) => Ok(Autopilot),
+            ("show-row", _) => Ok(ShowRow),
+            ("help", _) => Ok(Help),
+            ("quit", _) => Ok(Quit),
+            ("focus-left", _) => Ok(FocusLeft),
+            ("focus-right", _) => Ok(FocusRight),
+            ("focus-up", _) => Ok(FocusUp),
+            ("focus-down", _) => Ok(FocusDown),
+            _ => Err(KeymapError::UnknownAction(String::from(name))),
+        }
+    }
+}
+
+/// A key as written in the keymap file together with the event it produces.
+#[derive(Clone, Debug, Getters)]
+pub struct Binding
+{
+    #[getset(get = "pub")]
+    name: String,
+
+    #[getset(get = "pub")]
+    event: Event,
+}
+
+impl FromStr for Binding
+{
+    type Err = KeymapError;
+
+    /// Parses keys such as 'd', 'ctrl-z', 'alt-x', 'enter', 'space' and 'f1'. Single characters are
+    /// case sensitive while named keys are not.
+    fn from_str(name: &str) -> Result<Self, Self::Err>
+    {
+        let unknown_key = || KeymapError::UnknownKey(String::from(name));
+        let single_char = |s: &str| {
+            let mut chars = s.chars();
+            chars.next().filter(|_| chars.next().is_none())
+        };
+
+        let event = if let Some(c) = single_char(name) {
+            Event::Char(c)
+        } else {
+            let lowercase = name.to_lowercase();
+
+            if let Some(c) = lowercase.strip_prefix("ctrl-").and_then(single_char) {
+                Event::CtrlChar(c)
+            } else if let Some(c) = name.get(4..).filter(|_| lowercase.starts_with("alt-")) {
+                Event::AltChar(single_char(c).ok_or_else(unknown_key)?)
+            } else if let Some(n) = lowercase
+                .strip_prefix('f')
+                .and_then(|n| n.parse().ok())
+                .filter(|n| (1..=12).contains(n))
+            {
+                Event::Key(Key::from_f(n))
+            } else {
+                match lowercase.as_str() {
+                    "space" => Event::Char(' '),
+                    "enter" => Event::Key(Key::Enter),
+                    "tab" => Event::Key(Key::Tab),
+                    "backspace" => Event::Key(Key::Backspace),
+                    "esc" => Event::Key(Key::Esc),
+                    "left" => Event::Key(Key::Left),
+                    "right" => Event::Key(Key::Right),
+                    "up" => Event::Key(Key::Up),
+                    "down" => Event::Key(Key::Down),
+                    "insert" => Event::Key(Key::Ins),
+                    "delete" => Event::Key(Key::Del),
+                    "home" => Event::Key(Key::Home),
+                    "end" => Event::Key(Key::End),
+                    "pageup" => Event::Key(Key::PageUp),
+                    "pagedown" => Event::Key(Key::PageDown),
+                    _ => return Err(unknown_key()),
+                }
+            }
+        };
+
+        Ok(Binding {
+            name: String::from(name),
+            event,
+        })
+    }
+}
+
+/// Errors that can occur when a keymap is loaded.
+#[derive(Debug)]
+pub enum KeymapError
+{
+    Io(io::Error),
+    Syntax(toml::de::Error),
+    UnknownPreset(String),
+    UnknownAction(String),
+    UnknownKey(String),
+    ReservedKey(String),
+    /// A key bound to more than one action.
+    Conflict(String, Vec<Action>),
+}
+
+impl Display for KeymapError
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        use KeymapError::*;
+
+        match self {
+            Io(error) => write!(f, "{}", error),
+            Syntax(error) => write!(f, "{}", error),
+            UnknownPreset(name) => write!(f, "unknown preset '{}'", name),
+            UnknownAction(name) => write!(f, "unknown action '{}'", name),
+            UnknownKey(name) => write!(f, "unknown key '{}'", name),
+            ReservedKey(name) => write!(f, "the key '{}' is reserved", name),
+            Conflict(name, actions) => write!(
+                f,
+                "the key '{}' is bound to {}",
+                name,
+                actions
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(" and ")
+            ),
+        }
+    }
+}
+
+impl Error for KeymapError {}
+
+/// The contents of a keymap file.
+#[derive(Deserialize)]
+struct KeymapFile
+{
+    preset: Option<String>,
+
+    #[serde(default)]
+    bindings: BTreeMap<String, Vec<String>>,
+}
+
+/// Maps keys to actions.
+#[derive(Clone, Debug)]
+pub struct Keymap
+{
+    bindings: BTreeMap<Action, Vec<Binding>>,
+}
+
+impl Keymap
+{
+    /// The keymap with the given name, either 'default' or 'vim'.
+    pub fn preset(name: &str) -> Result<Keymap, KeymapError>
+    {
+        use Action::*;
+
+        let mut bindings = vec![
+            (Deal, vec!["d"]),
+            (Place, vec!["p"]),
+            (EliminateRow(0), vec!["1"]),
+            (EliminateRow(1), vec!["2"]),
+            (EliminateRow(2), vec!["3"]),
+            (EliminateRow(3), vec!["4"]),
+            (NewGame, vec!["n"]),
+            (Stats, vec!["s"]),
+            (History, vec!["r"]),
+            (Settings, vec!["o"]),
+            (Autopilot, vec!["a"]),
+            (ShowRow, vec!["+"]),
+            (Quit, vec!["q"]),
+        ];
+
+        match name {
+            "default" => {
+                bindings.push((Help, vec!["f1", "h"]));
+                bindings.push((Undo, vec!["u", "ctrl-z"]));
+                bindings.push((Hint, vec!["t"]));
+            }
+            "vim" => {
+                bindings.push((Help, vec!["f1"]));
+                bindings.push((Undo, vec!["u"]));
+                bindings.push((Hint, vec!["K"]));
+                bindings.push((FocusLeft, vec!["h"]));
+                bindings.push((FocusDown, vec!["j"]));
+                bindings.push((FocusUp, vec!["k"]));
+                bindings.push((FocusRight, vec!["l"]));
+            }
+            _ => return Err(KeymapError::UnknownPreset(String::from(name))),
+        }
+
+        let mut keymap = Keymap {
+            bindings: BTreeMap::new(),
+        };
+        for (action, keys) in bindings {
+            keymap.bind(action, &keys)?;
+        }
+        Ok(keymap)
+    }
+
+    /// Reads the keymap file at `path`. A missing file gives the default keymap.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Keymap, KeymapError>
+    {
+        match fs::read_to_string(path) {
+            Ok(content) => Keymap::parse(&content),
+            Err(error) if matches!(error.kind(), ErrorKind::NotFound) => Keymap::preset("default"),
+            Err(error) => Err(KeymapError::Io(error)),
+        }
+    }
+
+    /// Parses the contents of a keymap file. The bindings in the file replace those of the preset
+    /// for the same action.
+    pub fn parse(content: &str) -> Result<Keymap, KeymapError>
+    {
+        let file: KeymapFile = toml::from_str(content).map_err(KeymapError::Syntax)?;
+        let mut keymap = Keymap::preset(file.preset.as_deref().unwrap_or("default"))?;
+
+        for (action, keys) in file.bindings {
+            keymap.bind(action.parse()?, &keys)?;
+        }
+        keymap.check_conflicts()?;
+        Ok(keymap)
+    }
+
+    /// Every action together with the keys bound to it.
+    pub fn bindings(&self) -> impl Iterator<Item = (Action, &Binding)>
+    {
+        self.bindings
+            .iter()
+            .flat_map(|(&action, keys)| keys.iter().map(move |key| (action, key)))
+    }
+
+    fn bind<S: AsRef<str>>(&mut self, action: Action, keys: &[S]) -> Result<(), KeymapError>
+    {
+        let keys = keys
+            .iter()
+            .map(|key| key.as_ref().parse())
+            .collect::<Result<Vec<Binding>, _>>()?;
+
+        self.bindings.insert(action, keys);
+        Ok(())
+    }
+
+    fn check_conflicts(&self) -> Result<(), KeymapError>
+    {
+        let mut actions: HashMap<&Event, (&str, Vec<Action>)> = HashMap::new();
+
+        for (action, key) in self.bindings() {
+            let reserved = RESERVED_KEYS
+                .iter()
+                .map(|name| name.parse::<Binding>().unwrap())
+                .any(|reserved| reserved.event == key.event);
+            if reserved {
+                return Err(KeymapError::ReservedKey(key.name.clone()));
+            }
+
+            let (_, bound) = actions
+                .entry(&key.event)
+                .or_insert_with(|| (&key.name, Vec::new()));
+            if !bound.contains(&action) {
+                bound.push(action);
+            }
+        }
+
+        match actions.into_iter().find(|(_, (_, bound))| bound.len() > 1) {
+            Some((_, (name, bound))) => Err(KeymapError::Conflict(String::from(name), bound)),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn parse_keys()
+    {
+        let event = |name: &str| name.parse::<Binding>().unwrap().event;
+
+        assert_eq!(event("d"), Event::Char('d'));
+        assert_eq!(event("K"), Event::Char('K'));
+        assert_eq!(event("Ctrl-Z"), Event::CtrlChar('z'));
+        assert_eq!(event("alt-x"), Event::AltChar('x'));
+        assert_eq!(event("space"), Event::Char(' '));
+        assert_eq!(event("F1"), Event::Key(Key::F1));
+        assert_eq!(event("enter"), Event::Key(Key::Enter));
+        assert!(matches!(
+            "ctrl-enter".parse::<Binding>(),
+            Err(KeymapError::UnknownKey(_))
+        ));
+    }
+
+    #[test]
+    fn action_names_round_trip()
+    {
+        let keymap = Keymap::preset("vim").unwrap();
+
+        for (action, _) in keymap.bindings() {
+            assert_eq!(action.to_string().parse::<Action>().unwrap(), action);
+        }
+        assert!("eliminate-row-5".parse::<Action>().is_err());
+    }
+
+    #[test]
+    fn file_overrides_preset()
+    {
+        let keymap = Keymap::parse(
+            r#"
+            preset = "vim"
+
+            [bindings]
+            deal = ["space", "x"]
+            "#,
+        )
+        .unwrap();
+
+        let names = |action| {
+            keymap
+                .bindings()
+                .filter(|(bound, _)| *bound == action)
+                .map(|(_, key)| key.name().clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(Action::Deal), vec!["space", "x"]);
+        assert_eq!(names(Action::FocusLeft), vec!["h"]);
+    }
+
+    #[test]
+    fn conflicts_are_detected()
+    {
+        let result = Keymap::parse(
+            r#"
+            [bindings]
+            undo = ["d"]
+            "#,
+        );
+        assert!(matches!(result, Err(KeymapError::Conflict(name, _)) if name == "d"));
+
+        let result = Keymap::parse(
+            r#"
+            [bindings]
+            hint = ["?"]
+            "#,
+        );
+        assert!(matches!(result, Err(KeymapError::ReservedKey(_))));
+
+        for key in ["enter", "tab", "left", "right", "up", "down"] {
+            let result = Keymap::parse(&format!("[bindings]\nfocus-left = [\"{}\"]", key));
+            assert!(matches!(result, Err(KeymapError::ReservedKey(name)) if name == key));
+        }
+    }
+}
@@ -0,0 +1,288 @@
+use std::{
+    error::Error,
+    fmt::{
+        self,
+        Display,
+    },
+    fs,
+    io::{
+        self,
+        ErrorKind,
+    },
+    path::{
+        Path,
+        PathBuf,
+    },
+    time::Duration,
+};
+
+use getset::{
+    CopyGetters,
+    Getters,
+    Setters,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    archive,
+    game::{
+        card::{
+            Card,
+            Suit,
+        },
+        Strategy,
+    },
+};
+
+/// Path of the settings file that is read at startup.
+pub static DEFAULT_PATH: &str = "settings.toml";
+
+/// Errors that can occur when the settings are loaded or saved.
+#[derive(Debug)]
+pub enum SettingsError
+{
+    Io(io::Error),
+    Syntax(toml::de::Error),
+    Format(toml::ser::Error),
+}
+
+impl Display for SettingsError
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        use SettingsError::*;
+
+        match self {
+            Io(error) => write!(f, "{}", error),
+            Syntax(error) => write!(f, "{}", error),
+            Format(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl Error for SettingsError {}
+
+/// How cards are drawn on the table.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CardStyle
+{
+    /// Every card on a single line, e.g. '10<>'.
+    Compact,
+
+    /// Overlapping card faces with a border, showing the rank and suit in the corner of each card.
+    Bordered,
+}
+
+/// The symbols suits are written with.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SuitGlyphs
+{
+    /// '<<', '<3', 'cc' and '<>'.
+    Ascii,
+
+    /// '♠', '♡', '♣' and '♢', where the red suits are hollow so they stand out without colour.
+    Symbols,
+
+    /// 'S', 'H', 'C' and 'D'.
+    Letters,
+}
+
+impl SuitGlyphs
+{
+    pub fn suit(&self, suit: &Suit) -> String
+    {
+        let glyph = match (self, suit) {
+            (SuitGlyphs::Ascii, suit) => return String::from(suit),
+            (SuitGlyphs::Symbols, Suit::Spades) => "♠",
+            (SuitGlyphs::Symbols, Suit::Hearts) => "♡",
+            (SuitGlyphs::Symbols, Suit::Clubs) => "♣",
+            (SuitGlyphs::Symbols, Suit::Diamonds) => "♢",
+            (SuitGlyphs::Letters, Suit::Spades) => "S",
+            (SuitGlyphs::Letters, Suit::Hearts) => "H",
+            (SuitGlyphs::Letters, Suit::Clubs) => "C",
+            (SuitGlyphs::Letters, Suit::Diamonds) => "D",
+        };
+
+        String::from(glyph)
+    }
+
+    /// The rank of `card` followed by its suit, e.g. '10<>' or '10♢'.
+    pub fn card(&self, card: &Card) -> String
+    {
+        format!("{}{}", String::from(card.rank()), self.suit(card.suit()))
+    }
+}
+
+/// How fast the autopilot plays.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AutopilotSpeed
+{
+    Slow,
+    Normal,
+    Fast,
+}
+
+impl AutopilotSpeed
+{
+    /// The time between two moves.
+    pub fn interval(&self) -> Duration
+    {
+        match self {
+            AutopilotSpeed::Slow => Duration::from_millis(1500),
+            AutopilotSpeed::Normal => Duration::from_millis(750),
+            AutopilotSpeed::Fast => Duration::from_millis(250),
+        }
+    }
+}
+
+/// Preferences of the player. Settings missing from the file keep their default.
+#[derive(Clone, CopyGetters, Debug, Deserialize, Eq, Getters, PartialEq, Serialize, Setters)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Settings
+{
+    /// Whether to ask before a game that is not over yet is abandoned by starting a new game or
+    /// quitting.
+    #[getset(get_copy = "pub", set = "pub")]
+    confirm_abandon: bool,
+
+    /// Whether dealing, eliminating and placing cards is animated.
+    #[getset(get_copy = "pub", set = "pub")]
+    animations: bool,
+
+    /// Whether cards that are the only possible move are eliminated without asking the player.
+    #[getset(get_copy = "pub", set = "pub")]
+    auto_eliminate: bool,
+
+    #[getset(get_copy = "pub")]
+    card_style: CardStyle,
+
+    #[getset(get_copy = "pub", set = "pub")]
+    suit_glyphs: SuitGlyphs,
+
+    /// The preset of the theme, see `theme::preset`. Without one the theme is picked from the
+    /// environment.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub", set = "pub")]
+    theme: Option<String>,
+
+    /// The archive every game is recorded in.
+    #[getset(get = "pub", set = "pub")]
+    history_path: PathBuf,
+
+    /// How the autopilot picks its moves.
+    #[getset(get_copy = "pub", set = "pub")]
+    autopilot_strategy: Strategy,
+
+    #[getset(get_copy = "pub", set = "pub")]
+    autopilot_speed: AutopilotSpeed,
+
+    /// Whether to describe the game in lines of text instead of drawing the table.
+    #[getset(get_copy = "pub")]
+    screen_reader: bool,
+}
+
+impl Default for Settings
+{
+    fn default() -> Self
+    {
+        Settings {
+            confirm_abandon: true,
+            animations: true,
+            auto_eliminate: false,
+            card_style: CardStyle::Compact,
+            suit_glyphs: SuitGlyphs::Ascii,
+            theme: None,
+            history_path: PathBuf::from(archive::DEFAULT_PATH),
+            autopilot_strategy: Strategy::Greedy,
+            autopilot_speed: AutopilotSpeed::Normal,
+            screen_reader: false,
+        }
+    }
+}
+
+impl Settings
+{
+    /// Reads the settings file at `path`. A missing file gives the default settings.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Settings, SettingsError>
+    {
+        match fs::read_to_string(path) {
+            Ok(content) => Settings::parse(&content),
+            Err(error) if matches!(error.kind(), ErrorKind::NotFound) => Ok(Settings::default()),
+            Err(error) => Err(SettingsError::Io(error)),
+        }
+    }
+
+    pub fn parse(content: &str) -> Result<Settings, SettingsError>
+    {
+        toml::from_str(content).map_err(SettingsError::Syntax)
+    }
+
+    /// Writes the settings to `path`, replacing the file if it exists.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), SettingsError>
+    {
+        let content = toml::to_string(self).map_err(SettingsError::Format)?;
+
+        fs::write(path, content).map_err(SettingsError::Io)
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::game::card::Rank;
+
+    #[test]
+    fn parse_settings()
+    {
+        assert!(Settings::parse("").unwrap().confirm_abandon());
+        assert!(Settings::parse("").unwrap().animations());
+        assert_eq!(
+            Settings::parse("card-style = \"bordered\"")
+                .unwrap()
+                .card_style(),
+            CardStyle::Bordered
+        );
+        assert!(!Settings::parse("confirm-abandon = false")
+            .unwrap()
+            .confirm_abandon());
+        assert!(matches!(
+            Settings::parse("confirm-everything = true"),
+            Err(SettingsError::Syntax(_))
+        ));
+    }
+
+    #[test]
+    fn settings_survive_saving()
+    {
+        let mut settings = Settings::default();
+        settings
+            .set_suit_glyphs(SuitGlyphs::Letters)
+            .set_theme(Some(String::from("dark")))
+            .set_history_path(PathBuf::from("games/history.json"))
+            .set_autopilot_strategy(Strategy::Simple)
+            .set_autopilot_speed(AutopilotSpeed::Fast);
+
+        let content = toml::to_string(&settings).unwrap();
+        assert_eq!(Settings::parse(&content).unwrap(), settings);
+
+        let content = toml::to_string(&Settings::default()).unwrap();
+        assert_eq!(Settings::parse(&content).unwrap(), Settings::default());
+    }
+
+    #[test]
+    fn suit_glyphs()
+    {
+        let card = Card::new(Suit::Diamonds, Rank::Num(10));
+
+        assert_eq!(SuitGlyphs::Ascii.card(&card), "10<>");
+        assert_eq!(SuitGlyphs::Symbols.card(&card), "10♢");
+        assert_eq!(SuitGlyphs::Letters.card(&card), "10D");
+    }
+}
@@ -0,0 +1,326 @@
+use std::{
+    env,
+    error::Error,
+    fmt::{
+        self,
+        Display,
+    },
+    fs,
+    io::{
+        self,
+        ErrorKind,
+    },
+    path::Path,
+};
+
+use cursive::{
+    theme::{
+        self,
+        BaseColor,
+        Color,
+        ColorStyle,
+        Effect,
+        Palette,
+        Theme,
+    },
+    Printer,
+};
+use toml::{
+    value::Table,
+    Value,
+};
+
+use crate::game::card::Suit;
+
+/// Path of the theme file that is read at startup.
+pub static DEFAULT_PATH: &str = "theme.toml";
+
+/// The names of the presets.
+pub static PRESETS: [&str; 5] = ["default", "dark", "light", "colorblind", "monochrome"];
+
+/// The look of cursive with the colours of the cards added.
+static DEFAULT: &str = r#"
+[colors]
+selected = "light yellow"
+hearts = "red"
+diamonds = "red"
+spades = "black"
+clubs = "black"
+"#;
+
+static DARK: &str = r#"
+shadow = false
+borders = "simple"
+
+[colors]
+background = "black"
+shadow = "black"
+view = "black"
+primary = "light white"
+secondary = "white"
+tertiary = "light black"
+title_primary = "light yellow"
+title_secondary = "yellow"
+highlight = "blue"
+highlight_inactive = "light black"
+highlight_text = "light white"
+
+selected = "yellow"
+hearts = "light red"
+diamonds = "light red"
+spades = "light white"
+clubs = "light white"
+"#;
+
+static LIGHT: &str = r#"
+shadow = false
+borders = "simple"
+
+[colors]
+background = "light white"
+shadow = "white"
+view = "light white"
+primary = "black"
+secondary = "light black"
+tertiary = "blue"
+title_primary = "blue"
+title_secondary = "magenta"
+highlight = "blue"
+highlight_inactive = "white"
+highlight_text = "light white"
+
+selected = "light yellow"
+hearts = "red"
+diamonds = "red"
+spades = "black"
+clubs = "black"
+"#;
+
+/// Four suit colours that can be told apart with the common forms of colour blindness, taken from
+/// the palette of Okabe and Ito.
+static COLORBLIND: &str = r##"
+[colors]
+selected = "#f0e442"
+hearts = "#d55e00"
+diamonds = "#0072b2"
+spades = "black"
+clubs = "#009e73"
+"##;
+
+/// No colours but those of the terminal. Cards and widgets of the game are selected in reverse
+/// video, but cursive can only mark its own widgets, e.g. buttons, with colours.
+static MONOCHROME: &str = r#"
+shadow = false
+borders = "simple"
+
+[colors]
+background = "default"
+shadow = "default"
+view = "default"
+primary = "default"
+secondary = "default"
+tertiary = "default"
+title_primary = "default"
+title_secondary = "default"
+highlight = "white"
+highlight_inactive = "default"
+highlight_text = "black"
+
+selected = "default"
+hearts = "default"
+diamonds = "default"
+spades = "default"
+clubs = "default"
+"#;
+
+/// Errors that can occur when a theme is loaded.
+#[derive(Debug)]
+pub enum ThemeError
+{
+    Io(io::Error),
+    Syntax(toml::de::Error),
+    UnknownPreset(String),
+}
+
+impl Display for ThemeError
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        use ThemeError::*;
+
+        match self {
+            Io(error) => write!(f, "{}", error),
+            Syntax(error) => write!(f, "{}", error),
+            UnknownPreset(name) => write!(f, "unknown preset '{}'", name),
+        }
+    }
+}
+
+impl Error for ThemeError {}
+
+/// The theme with the given name, either 'default', 'dark', 'light', 'colorblind' or 'monochrome'.
+pub fn preset(name: &str) -> Result<Theme, ThemeError>
+{
+    build(preset_table(name)?)
+}
+
+/// Reads the theme file at `path`, which inherits from `base` unless it names a preset itself. A
+/// missing file gives the `base` preset. Without a `base` the default preset is used, or the
+/// monochrome one if the `NO_COLOR` environment variable is set.
+pub fn load<P: AsRef<Path>>(path: P, base: Option<&str>) -> Result<Theme, ThemeError>
+{
+    let base = base.unwrap_or_else(|| {
+        let no_color = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+
+        if no_color {
+            "monochrome"
+        } else {
+            "default"
+        }
+    });
+
+    match fs::read_to_string(path) {
+        Ok(content) => parse(&content, base),
+        Err(error) if matches!(error.kind(), ErrorKind::NotFound) => preset(base),
+        Err(error) => Err(ThemeError::Io(error)),
+    }
+}
+
+/// Parses the contents of a theme file. The file has the format of a cursive theme and may name a
+/// preset it inherits from instead of `base`. Settings and colours in the file replace those of the
+/// preset.
+pub fn parse(content: &str, base: &str) -> Result<Theme, ThemeError>
+{
+    let mut file: Table = toml::from_str(content).map_err(ThemeError::Syntax)?;
+
+    let name = match file.remove("preset") {
+        Some(Value::String(name)) => name,
+        _ => String::from(base),
+    };
+    let mut table = preset_table(&name)?;
+
+    for (key, value) in file {
+        match (table.get_mut(&key), value) {
+            (Some(Value::Table(colors)), Value::Table(overrides)) if key == "colors" => {
+                colors.extend(overrides);
+            }
+            (_, value) => {
+                table.insert(key, value);
+            }
+        }
+    }
+    build(table)
+}
+
+/// The background colour of selected cards and widgets.
+pub fn selected(palette: &Palette) -> Color
+{
+    palette
+        .custom("selected")
+        .copied()
+        .unwrap_or(Color::Light(BaseColor::Yellow))
+}
+
+/// True if the theme has no colour for selections, which are then shown in reverse video.
+pub fn is_monochrome(palette: &Palette) -> bool
+{
+    selected(palette) == Color::TerminalDefault
+}
+
+/// Draws with `f` the way selected cards and widgets are drawn, using `front` for the text.
+pub fn with_selected<F: FnOnce(&Printer)>(printer: &Printer, front: Color, f: F)
+{
+    if is_monochrome(&printer.theme.palette) {
+        printer.with_color(ColorStyle::front(front), |printer| {
+            printer.with_effect(Effect::Reverse, f)
+        });
+    } else {
+        printer.with_color(ColorStyle::new(front, selected(&printer.theme.palette)), f);
+    }
+}
+
+/// The colour cards of `suit` are drawn with.
+pub fn suit(palette: &Palette, suit: &Suit) -> Color
+{
+    let (key, fallback) = match suit {
+        Suit::Hearts => ("hearts", BaseColor::Red),
+        Suit::Diamonds => ("diamonds", BaseColor::Red),
+        Suit::Spades => ("spades", BaseColor::Black),
+        Suit::Clubs => ("clubs", BaseColor::Black),
+    };
+
+    palette
+        .custom(key)
+        .copied()
+        .unwrap_or(Color::Dark(fallback))
+}
+
+fn preset_table(name: &str) -> Result<Table, ThemeError>
+{
+    let content = match name {
+        "default" => DEFAULT,
+        "dark" => DARK,
+        "light" => LIGHT,
+        "colorblind" => COLORBLIND,
+        "monochrome" => MONOCHROME,
+        _ => return Err(ThemeError::UnknownPreset(String::from(name))),
+    };
+
+    Ok(toml::from_str(content).unwrap())
+}
+
+fn build(table: Table) -> Result<Theme, ThemeError>
+{
+    theme::load_toml(&Value::Table(table).to_string()).map_err(|error| match error {
+        theme::Error::Io(error) => ThemeError::Io(error),
+        theme::Error::Parse(error) => ThemeError::Syntax(error),
+    })
+}
+
+#[cfg(test)]
+mod tests
+{
+    use cursive::theme::PaletteColor;
+
+    use super::*;
+
+    #[test]
+    fn presets_have_card_colours()
+    {
+        for name in PRESETS {
+            let theme = preset(name).unwrap();
+
+            assert!(theme.palette.custom("selected").is_some());
+            assert!(theme.palette.custom("spades").is_some());
+        }
+        assert!(matches!(preset("neon"), Err(ThemeError::UnknownPreset(_))));
+        assert!(is_monochrome(&preset("monochrome").unwrap().palette));
+        assert!(!is_monochrome(&preset("colorblind").unwrap().palette));
+    }
+
+    #[test]
+    fn file_inherits_from_preset()
+    {
+        let theme = parse(
+            r##"
+            preset = "dark"
+
+            [colors]
+            hearts = "#ff0000"
+            "##,
+            "light",
+        )
+        .unwrap();
+
+        assert_eq!(suit(&theme.palette, &Suit::Hearts), Color::Rgb(255, 0, 0));
+        assert_eq!(
+            suit(&theme.palette, &Suit::Spades),
+            Color::Light(BaseColor::White)
+        );
+        assert_eq!(
+            theme.palette[PaletteColor::View],
+            Color::Dark(BaseColor::Black)
+        );
+        assert!(!theme.shadow);
+    }
+}
@@ -1,7 +1,13 @@
+mod autopilot_controller;
 mod model_controller;
+mod replay_controller;
+mod settings_controller;
+mod text_controller;
 mod view_controller;
 
 pub use model_controller::ModelController;
+pub use replay_controller::ReplayController;
+pub use text_controller::TextController;
 pub use view_controller::{
     Mode,
     ViewController,
@@ -0,0 +1,87 @@
+use std::{
+    cell::RefCell,
+    rc::Weak,
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+use cursive::Cursive;
+
+use crate::{
+    config::Settings,
+    controllers::ModelController,
+    game::Strategy,
+};
+
+/// Plays the current game by itself, one move every interval. The moves are made through the model
+/// controller, so the views are updated the same way as when the player moves.
+pub struct AutopilotController
+{
+    model_controller: Weak<RefCell<ModelController>>,
+    strategy: Strategy,
+    interval: Duration,
+    running: bool,
+
+    /// When the autopilot last moved or was started.
+    last_move: Instant,
+}
+
+impl AutopilotController
+{
+    pub fn new(
+        model_controller: Weak<RefCell<ModelController>>,
+        settings: &Settings,
+    ) -> AutopilotController
+    {
+        AutopilotController {
+            model_controller,
+            strategy: settings.autopilot_strategy(),
+            interval: settings.autopilot_speed().interval(),
+            running: false,
+            last_move: Instant::now(),
+        }
+    }
+
+    /// Uses the strategy and speed of `settings` from the next move on.
+    pub fn configure(&mut self, settings: &Settings)
+    {
+        self.strategy = settings.autopilot_strategy();
+        self.interval = settings.autopilot_speed().interval();
+    }
+
+    pub fn is_running(&self) -> bool
+    {
+        self.running
+    }
+
+    /// Starts playing, or hands the game back to the player.
+    pub fn toggle(&mut self)
+    {
+        self.running = !self.running;
+        self.last_move = Instant::now();
+    }
+
+    /// Makes the next move once the interval has passed. The autopilot stops when there is no move
+    /// left to make, e.g. because the game is over.
+    pub fn on_refresh(&mut self, s: &mut Cursive)
+    {
+        if !self.running || self.last_move.elapsed() < self.interval {
+            return;
+        }
+
+        let model_controller = match self.model_controller.upgrade() {
+            Some(model_controller) => model_controller,
+            None => return,
+        };
+
+        let next = model_controller.borrow().suggest(self.strategy);
+        if let Some(legal_move) = next {
+            model_controller.borrow_mut().play(s, legal_move);
+            self.last_move = Instant::now();
+        }
+
+        self.running = model_controller.borrow().suggest(self.strategy).is_some();
+    }
+}
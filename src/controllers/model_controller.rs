@@ -1,35 +1,69 @@
 use std::{
-    collections::HashMap,
-    fs,
+    path::{
+        Path,
+        PathBuf,
+    },
+    time,
 };
 
+use chrono::{
+    DateTime,
+    Duration,
+    Local,
+};
 use cursive::Cursive;
 use getset::Setters;
-use uuid::Uuid;
-
-use crate::game::{
-    Deck,
-    Event,
-    Game,
-    GameError,
-    GameResult,
-    History,
-    RowIndex,
+
+use crate::{
+    archive,
+    game::{
+        Deck,
+        DiscardPile,
+        Event,
+        Game,
+        GameError,
+        GameResult,
+        History,
+        Move,
+        RowIndex,
+        Strategy,
+        Table,
+    },
 };
 
+/// The longest time between two refreshes that is not taken as the application being suspended.
+/// Refreshes come several times a second while the application runs.
+static SUSPENDED_AFTER: time::Duration = time::Duration::from_secs(2);
+
 type OnEventListener = Box<dyn Fn(&mut Cursive, &Event)>;
-type OnErrorListener = Box<dyn Fn(&GameError)>;
+type OnErrorListener = Box<dyn Fn(&mut Cursive, &GameError)>;
 type OnNewGameListener = Box<dyn Fn(&mut Cursive)>;
 type OnCheckDealPossible = Box<dyn Fn(&mut Cursive, bool)>;
 type OnCheckPlacePossible = Box<dyn Fn(&mut Cursive, bool)>;
 type OnDeckSizeChangedListener = Box<dyn Fn(&mut Cursive, usize)>;
+type OnDiscardPileChangedListener = Box<dyn Fn(&mut Cursive, &DiscardPile)>;
 type OnGameOverListener = Box<dyn Fn(&mut Cursive, GameResult)>;
+type OnTableChangedListener = Box<dyn Fn(&mut Cursive, &Table)>;
+type OnHintListener = Box<dyn Fn(&mut Cursive, Option<Move>)>;
+type OnLegalMovesChangedListener = Box<dyn Fn(&mut Cursive, &[Move])>;
 
 #[derive(Setters)]
 pub struct ModelController
 {
     game: Option<Game>,
 
+    /// The archive finished and abandoned games are recorded in.
+    #[getset(set = "pub")]
+    history_path: PathBuf,
+
+    /// Whether cards are eliminated without asking while that is all that can be done, see
+    /// `Game::forced_elimination`.
+    #[getset(set = "pub")]
+    auto_eliminate: bool,
+
+    /// When `on_refresh` was last called.
+    last_refresh: Option<DateTime<Local>>,
+
     #[getset(set = "pub")]
     on_event: Option<OnEventListener>,
 
@@ -42,6 +76,9 @@ pub struct ModelController
     #[getset(set = "pub")]
     on_deck_size_changed: Option<OnDeckSizeChangedListener>,
 
+    #[getset(set = "pub")]
+    on_discard_pile_changed: Option<OnDiscardPileChangedListener>,
+
     #[getset(set = "pub")]
     on_check_deal_possible: Option<OnCheckDealPossible>,
 
@@ -50,6 +87,18 @@ pub struct ModelController
 
     #[getset(set = "pub")]
     on_game_over: Option<OnGameOverListener>,
+
+    /// Called with the whole table when it has changed in a way that can not be described by an
+    /// event, e.g. after an undo.
+    #[getset(set = "pub")]
+    on_table_changed: Option<OnTableChangedListener>,
+
+    #[getset(set = "pub")]
+    on_hint: Option<OnHintListener>,
+
+    /// Called with the moves that can be made after every change to the game.
+    #[getset(set = "pub")]
+    on_legal_moves_changed: Option<OnLegalMovesChangedListener>,
 }
 
 impl ModelController
@@ -58,19 +107,31 @@ impl ModelController
     {
         ModelController {
             game: None,
+            history_path: PathBuf::from(archive::DEFAULT_PATH),
+            auto_eliminate: false,
+            last_refresh: None,
             on_event: None,
             on_error: None,
             on_new_game: None,
             on_deck_size_changed: None,
+            on_discard_pile_changed: None,
             on_check_deal_possible: None,
             on_check_place_possible: None,
             on_game_over: None,
+            on_table_changed: None,
+            on_hint: None,
+            on_legal_moves_changed: None,
         }
     }
 
     pub fn drop_game(&mut self)
     {
-        self.game.take().map(Game::end).map(save_history);
+        let path = &self.history_path;
+
+        self.game
+            .take()
+            .map(Game::end)
+            .map(|history| save_history(history, path));
     }
 
     pub fn new_game(&mut self, s: &mut Cursive)
@@ -80,8 +141,10 @@ impl ModelController
 
         self.on_new_game.as_ref().map(|listener| listener(s));
         self.call_on_deck_size_changed(s);
+        self.call_on_discard_pile_changed(s);
         self.call_on_check_deal_possible(s);
         self.call_on_check_place_possible(s);
+        self.call_on_legal_moves_changed(s);
     }
 
     pub fn deal(&mut self, s: &mut Cursive)
@@ -93,10 +156,12 @@ impl ModelController
                     self.call_on_deck_size_changed(s);
                     self.call_on_check_deal_possible(s);
                     self.call_on_check_place_possible(s);
+                    self.call_on_legal_moves_changed(s);
                     self.call_on_game_over_if_game_is_over(s);
+                    self.eliminate_forced(s);
                 }
                 Err(error) => {
-                    self.on_error.as_ref().map(|listener| listener(&error));
+                    self.on_error.as_ref().map(|listener| listener(s, &error));
                 }
             };
         }
@@ -108,12 +173,15 @@ impl ModelController
             match result {
                 Ok(event) => {
                     self.on_event.as_ref().map(|listener| listener(s, event));
+                    self.call_on_discard_pile_changed(s);
                     self.call_on_check_deal_possible(s);
                     self.call_on_check_place_possible(s);
+                    self.call_on_legal_moves_changed(s);
                     self.call_on_game_over_if_game_is_over(s);
+                    self.eliminate_forced(s);
                 }
                 Err(error) => {
-                    self.on_error.as_ref().map(|listener| listener(&error));
+                    self.on_error.as_ref().map(|listener| listener(s, &error));
                 }
             };
         }
@@ -127,15 +195,137 @@ impl ModelController
                     self.on_event.as_ref().map(|listener| listener(s, event));
                     self.call_on_check_deal_possible(s);
                     self.call_on_check_place_possible(s);
+                    self.call_on_legal_moves_changed(s);
                     self.call_on_game_over_if_game_is_over(s);
+                    self.eliminate_forced(s);
                 }
                 Err(error) => {
-                    self.on_error.as_ref().map(|listener| listener(&error));
+                    self.on_error.as_ref().map(|listener| listener(s, &error));
                 }
             };
         }
     }
 
+    pub fn undo(&mut self, s: &mut Cursive)
+    {
+        if let Some(result) = self.game.as_mut().map(Game::undo) {
+            match result {
+                Ok(_) => self.resync(s),
+                Err(error) => self.call_on_error(s, &error),
+            };
+        }
+    }
+
+    /// Makes `legal_move` the same way as the player would.
+    pub fn play(&mut self, s: &mut Cursive, legal_move: Move)
+    {
+        match legal_move {
+            Move::Deal => self.deal(s),
+            Move::Eliminate(index) => self.eliminate(s, index),
+            Move::Place(from, to) => self.place(s, from, to),
+        }
+    }
+
+    /// The move `strategy` would make on the current table, if there is any.
+    pub fn suggest(&self, strategy: Strategy) -> Option<Move>
+    {
+        self.game.as_ref().and_then(|game| strategy.choose(game))
+    }
+
+    /// Checks if any card can be placed, reporting the reason to the error listener if not.
+    pub fn check_place_possible(&self, s: &mut Cursive) -> bool
+    {
+        self.check(s, Game::check_place_possible)
+    }
+
+    /// Checks if the card in the row at `index` can be placed, reporting the reason to the error
+    /// listener if not.
+    pub fn check_place_from(&self, s: &mut Cursive, index: RowIndex) -> bool
+    {
+        self.check(s, |game| game.check_place_from(index))
+    }
+
+    /// Suggests a move that is possible on the current table.
+    pub fn hint(&self, s: &mut Cursive)
+    {
+        if let (Some(listener), Some(game)) = (&self.on_hint, &self.game) {
+            listener(s, game.legal_moves().first().copied());
+        }
+    }
+
+    /// Called on every refresh. A long gap since the previous refresh means the application was
+    /// suspended, which is recorded so the time is not counted as spent on the game.
+    pub fn on_refresh(&mut self)
+    {
+        let now = Local::now();
+
+        if let (Some(last_refresh), Some(game)) = (self.last_refresh, self.game.as_mut()) {
+            let suspended = (now - last_refresh)
+                .to_std()
+                .is_ok_and(|gap| gap > SUSPENDED_AFTER);
+            if suspended {
+                game.suspend(last_refresh, now);
+            }
+        }
+        self.last_refresh = Some(now);
+    }
+
+    /// Whether the current game would be abandoned by ending it, see `Game::is_undecided`.
+    pub fn is_undecided(&self) -> bool
+    {
+        self.game.as_ref().is_some_and(Game::is_undecided)
+    }
+
+    /// Time spent on the current game, see `Game::elapsed`.
+    pub fn elapsed(&self) -> Option<Duration>
+    {
+        self.game.as_ref().map(Game::elapsed)
+    }
+
+    /// Eliminates the next card if auto-eliminate is on and the player has no choice to make. Every
+    /// elimination checks again, so this goes on until a real choice appears.
+    fn eliminate_forced(&mut self, s: &mut Cursive)
+    {
+        if !self.auto_eliminate {
+            return;
+        }
+        if let Some(index) = self.game.as_ref().and_then(Game::forced_elimination) {
+            self.eliminate(s, index);
+        }
+    }
+
+    /// Updates every listener with the current state of the game.
+    fn resync(&self, s: &mut Cursive)
+    {
+        if let (Some(listener), Some(game)) = (&self.on_table_changed, &self.game) {
+            listener(s, game.table());
+        }
+        self.call_on_deck_size_changed(s);
+        self.call_on_discard_pile_changed(s);
+        self.call_on_check_deal_possible(s);
+        self.call_on_check_place_possible(s);
+        self.call_on_legal_moves_changed(s);
+    }
+
+    fn check<F: Fn(&Game) -> Result<(), GameError>>(&self, s: &mut Cursive, check: F) -> bool
+    {
+        match self.game.as_ref().map(check) {
+            Some(Ok(())) => true,
+            Some(Err(error)) => {
+                self.call_on_error(s, &error);
+                false
+            }
+            None => false,
+        }
+    }
+
+    fn call_on_error(&self, s: &mut Cursive, error: &GameError)
+    {
+        if let Some(listener) = &self.on_error {
+            listener(s, error);
+        }
+    }
+
     fn call_on_deck_size_changed(&self, s: &mut Cursive)
     {
         self.on_deck_size_changed.as_ref().map(|listener| {
@@ -147,6 +337,13 @@ impl ModelController
         });
     }
 
+    fn call_on_discard_pile_changed(&self, s: &mut Cursive)
+    {
+        if let (Some(listener), Some(game)) = (&self.on_discard_pile_changed, &self.game) {
+            listener(s, game.discard_pile());
+        }
+    }
+
     fn call_on_check_deal_possible(&self, s: &mut Cursive)
     {
         self.on_check_deal_possible.as_ref().map(|listener| {
@@ -160,16 +357,20 @@ impl ModelController
     fn call_on_check_place_possible(&self, s: &mut Cursive)
     {
         self.on_check_place_possible.as_ref().map(|listener| {
-            self.game.as_ref().map(Game::table).map(|table| {
-                listener(
-                    s,
-                    table.iter().map(Vec::len).any(|len| len > 1)
-                        && table.iter().map(Vec::is_empty).any(|x| x),
-                );
-            });
+            self.game
+                .as_ref()
+                .map(Game::check_place_possible)
+                .map(|result| listener(s, result.is_ok()));
         });
     }
 
+    fn call_on_legal_moves_changed(&self, s: &mut Cursive)
+    {
+        if let (Some(listener), Some(game)) = (&self.on_legal_moves_changed, &self.game) {
+            listener(s, &game.legal_moves());
+        }
+    }
+
     fn call_on_game_over_if_game_is_over(&self, s: &mut Cursive)
     {
         self.on_game_over.as_ref().map(|listener| {
@@ -182,26 +383,17 @@ impl ModelController
     }
 }
 
-fn save_history(history: History) -> Result<(), std::io::Error>
+impl Drop for ModelController
 {
-    use std::io::ErrorKind::NotFound;
-
-    let history_path = "history.json";
-
-    match fs::read_to_string(history_path) {
-        Err(error) => {
-            if matches!(error.kind(), NotFound) {
-                fs::write(history_path, "{}")?;
-                save_history(history)
-            } else {
-                Err(error)
-            }
-        }
-        Ok(data) => {
-            let mut archive: HashMap<Uuid, History> = serde_json::from_str(&data).unwrap();
-            let uuid = Uuid::new_v4();
-            archive.insert(uuid, history);
-            fs::write(history_path, serde_json::to_string(&archive).unwrap())
-        }
+    /// Records the current game when the program ends in any other way than the Quit action, e.g.
+    /// by Ctrl-C.
+    fn drop(&mut self)
+    {
+        self.drop_game();
     }
 }
+
+fn save_history(history: History, path: &Path) -> Result<(), std::io::Error>
+{
+    archive::append(path, history)
+}
@@ -0,0 +1,245 @@
+use std::{
+    cell::RefCell,
+    cmp::Reverse,
+    iter::zip,
+    rc::Rc,
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+use cursive::{
+    event::Event,
+    view::{
+        Finder,
+        Nameable,
+        Resizable,
+        Scrollable,
+    },
+    views::{
+        Button,
+        Dialog,
+        DummyView,
+        LinearLayout,
+        OnEventView,
+        SelectView,
+        StackView,
+        TextView,
+    },
+    Cursive,
+};
+
+use crate::{
+    archive,
+    config::Settings,
+    game::History,
+    views::RowView,
+};
+
+static ROW_NAMES: [&str; 4] = [
+    "replay-row-0",
+    "replay-row-1",
+    "replay-row-2",
+    "replay-row-3",
+];
+
+/// The time each event is shown while a replay is playing.
+static EVENT_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Replays a recorded game one event at a time.
+pub struct ReplayController
+{
+    history: History,
+
+    /// The amount of events that have been replayed.
+    step: usize,
+
+    playing: bool,
+
+    /// When the replay last moved to another event.
+    last_step: Instant,
+}
+
+impl ReplayController
+{
+    /// Shows a list of the games in the archive of `settings`. Selecting a game replays it with
+    /// the cards drawn the way `settings` ask for.
+    pub fn show_archive(s: &mut Cursive, settings: &Settings)
+    {
+        let archive = match archive::load(settings.history_path()) {
+            Ok(archive) => archive,
+            Err(error) => {
+                s.add_layer(Dialog::info(format!(
+                    "The game history could not be read: {}",
+                    error
+                )));
+                return;
+            }
+        };
+
+        let mut games: Vec<History> = archive
+            .into_values()
+            .filter(|history| !history.events().is_empty())
+            .collect();
+        if games.is_empty() {
+            s.add_layer(Dialog::info("No games have been recorded yet."));
+            return;
+        }
+        games.sort_by_key(|history| Reverse(*history.start_date()));
+
+        let mut list = SelectView::new();
+        for history in games {
+            let date = history
+                .start_date()
+                .map(|date| date.format("%Y-%m-%d %H:%M").to_string())
+                .unwrap_or_else(|| String::from("Unknown date    "));
+            let label = format!(
+                "{}  {:<9} {:>3} moves",
+                date,
+                history.outcome(),
+                history.events().len()
+            );
+            list.add_item(label, history);
+        }
+        let settings = settings.clone();
+        list.set_on_submit(move |s, history: &History| {
+            ReplayController::show(s, history.clone(), &settings)
+        });
+
+        s.add_layer(
+            Dialog::around(list.scrollable().max_height(20))
+                .title("History")
+                .dismiss_button("Close"),
+        );
+    }
+
+    /// Shows the table of `history` before its first event together with controls to step through
+    /// the events.
+    fn show(s: &mut Cursive, history: History, settings: &Settings)
+    {
+        let controller = Rc::new(RefCell::new(ReplayController {
+            history,
+            step: 0,
+            playing: false,
+            last_step: Instant::now(),
+        }));
+
+        let mut rows = LinearLayout::horizontal();
+        for name in ROW_NAMES {
+            let row = RowView::new()
+                .with_card_style(settings.card_style())
+                .with_suit_glyphs(settings.suit_glyphs());
+            let width = row.width();
+
+            rows.add_child(row.with_name(name).fixed_width(width));
+        }
+
+        let button = |label: &str, action: fn(&mut ReplayController, &mut StackView)| {
+            let controller = Rc::clone(&controller);
+
+            Button::new(label, move |s| {
+                action(&mut controller.borrow_mut(), s.screen_mut())
+            })
+        };
+
+        let controls = LinearLayout::horizontal()
+            .child(button("Prev", |controller, s| {
+                controller.go_to(s, controller.step.saturating_sub(1))
+            }))
+            .child(DummyView.fixed_width(1))
+            .child(button("Play", ReplayController::toggle_play).with_name("replay-play"))
+            .child(DummyView.fixed_width(1))
+            .child(button("Next", |controller, s| {
+                controller.go_to(s, controller.step + 1)
+            }));
+
+        let dialog = Dialog::around(
+            LinearLayout::vertical()
+                .child(rows.min_height(13))
+                .child(TextView::new("").with_name("replay-status"))
+                .child(DummyView)
+                .child(controls),
+        )
+        .title("Replay")
+        .dismiss_button("Close");
+
+        // The refresh is passed on, so the callbacks of the table behind the replay keep running.
+        s.add_layer(
+            OnEventView::new(dialog).on_pre_event_inner(Event::Refresh, {
+                let controller = Rc::clone(&controller);
+
+                move |dialog, _| {
+                    controller.borrow_mut().on_refresh(dialog);
+                    None
+                }
+            }),
+        );
+        controller.borrow().update(s.screen_mut());
+    }
+
+    /// Shows the table after the first `step` events.
+    fn go_to<F: Finder>(&mut self, s: &mut F, step: usize)
+    {
+        self.step = step.min(self.history.events().len());
+        self.last_step = Instant::now();
+        self.update(s);
+    }
+
+    fn toggle_play<F: Finder>(&mut self, s: &mut F)
+    {
+        if self.playing {
+            self.stop();
+        } else {
+            // Playing a game that has been replayed to the end starts it over.
+            if self.step == self.history.events().len() {
+                self.step = 0;
+            }
+            self.playing = true;
+            self.last_step = Instant::now();
+        }
+        self.update(s);
+    }
+
+    fn stop(&mut self)
+    {
+        self.playing = false;
+    }
+
+    /// Moves to the next event when it is time to. The screen is refreshed a few times per second
+    /// as set up by the `ViewController`.
+    fn on_refresh<F: Finder>(&mut self, s: &mut F)
+    {
+        if !self.playing || self.last_step.elapsed() < EVENT_INTERVAL {
+            return;
+        }
+
+        self.go_to(s, self.step + 1);
+        if self.step == self.history.events().len() {
+            self.stop();
+            self.update(s);
+        }
+    }
+
+    /// Shows the current step in the views of the replay, which are found in `s`.
+    fn update<F: Finder>(&self, s: &mut F)
+    {
+        let table = self.history.replay(self.step);
+        for (name, cards) in zip(ROW_NAMES, table) {
+            s.call_on_name(name, |view: &mut RowView| view.set_cards(cards));
+        }
+
+        let events = self.history.events();
+        let status = match self.step.checked_sub(1).map(|index| &events[index]) {
+            Some(event) => format!("Move {} of {}: {}", self.step, events.len(), event),
+            None => format!("Before the first of {} moves", events.len()),
+        };
+        s.call_on_name("replay-status", |view: &mut TextView| {
+            view.set_content(status)
+        });
+
+        s.call_on_name("replay-play", |button: &mut Button| {
+            button.set_label(if self.playing { "Pause" } else { "Play" })
+        });
+    }
+}
@@ -0,0 +1,282 @@
+use std::{
+    cell::RefCell,
+    path::PathBuf,
+    rc::{
+        Rc,
+        Weak,
+    },
+};
+
+use cursive::{
+    view::{
+        Nameable,
+        Resizable,
+    },
+    views::{
+        Checkbox,
+        Dialog,
+        EditView,
+        LinearLayout,
+        SelectView,
+        TextView,
+    },
+    Cursive,
+    View,
+};
+
+use super::{
+    autopilot_controller::AutopilotController,
+    ModelController,
+};
+use crate::{
+    config::{
+        settings::{
+            self,
+            AutopilotSpeed,
+            SuitGlyphs,
+        },
+        theme,
+        Settings,
+    },
+    game::{
+        card::Suit,
+        Strategy,
+    },
+};
+
+/// The width of the labels in front of the settings.
+static LABEL_WIDTH: usize = 16;
+
+/// Shows the settings dialog. Saved settings replace `settings` and take effect right away: the
+/// theme is switched, the model controller and the autopilot are reconfigured and `on_change` is
+/// called for what only the calling controller shows.
+pub(super) fn change_settings<F>(
+    s: &mut Cursive,
+    settings: &Rc<RefCell<Settings>>,
+    model_controller: &Weak<RefCell<ModelController>>,
+    autopilot: &Weak<RefCell<AutopilotController>>,
+    on_change: F,
+) where
+    F: Fn(&mut Cursive, &Settings) + 'static,
+{
+    let current = settings.borrow().clone();
+    let settings = Rc::clone(settings);
+    let model_controller = Weak::clone(model_controller);
+    let autopilot = Weak::clone(autopilot);
+
+    show_settings(s, &current, move |s, changed| {
+        *settings.borrow_mut() = changed.clone();
+
+        apply_theme(s, changed);
+        if let Some(model_controller) = model_controller.upgrade() {
+            model_controller
+                .borrow_mut()
+                .set_history_path(changed.history_path().clone())
+                .set_auto_eliminate(changed.auto_eliminate());
+        }
+        if let Some(autopilot) = autopilot.upgrade() {
+            autopilot.borrow_mut().configure(changed);
+        }
+        on_change(s, changed);
+    });
+}
+
+/// Switches to the theme `settings` ask for. The theme file still replaces its colours.
+fn apply_theme(s: &mut Cursive, settings: &Settings)
+{
+    match theme::load(theme::DEFAULT_PATH, settings.theme().as_deref()) {
+        Ok(theme) => s.set_theme(theme),
+        Err(error) => s.add_layer(Dialog::info(format!("{}: {}", theme::DEFAULT_PATH, error))),
+    }
+}
+
+/// Shows a dialog to change `settings`. Saving writes the changed settings to the settings file and
+/// calls `on_save` with them so they can be applied right away.
+fn show_settings<F>(s: &mut Cursive, settings: &Settings, on_save: F)
+where
+    F: Fn(&mut Cursive, &Settings) + 'static,
+{
+    let mut suit_glyphs = SelectView::new().popup();
+    for (index, &glyphs) in [SuitGlyphs::Ascii, SuitGlyphs::Symbols, SuitGlyphs::Letters]
+        .iter()
+        .enumerate()
+    {
+        let symbols: Vec<String> = Suit::all().iter().map(|suit| glyphs.suit(suit)).collect();
+
+        suit_glyphs.add_item(format!("{:?} ({})", glyphs, symbols.join(" ")), glyphs);
+        if glyphs == settings.suit_glyphs() {
+            suit_glyphs.set_selection(index);
+        }
+    }
+
+    let mut themes = SelectView::new().popup().item("Automatic", None);
+    for (index, &name) in theme::PRESETS.iter().enumerate() {
+        themes.add_item(name, Some(String::from(name)));
+        if settings.theme().as_deref() == Some(name) {
+            themes.set_selection(index + 1);
+        }
+    }
+
+    let mut strategies = SelectView::new().popup();
+    for (index, &strategy) in [Strategy::Simple, Strategy::Greedy].iter().enumerate() {
+        strategies.add_item(format!("{:?}", strategy), strategy);
+        if strategy == settings.autopilot_strategy() {
+            strategies.set_selection(index);
+        }
+    }
+
+    let mut speeds = SelectView::new().popup();
+    for (index, &speed) in [
+        AutopilotSpeed::Slow,
+        AutopilotSpeed::Normal,
+        AutopilotSpeed::Fast,
+    ]
+    .iter()
+    .enumerate()
+    {
+        speeds.add_item(format!("{:?}", speed), speed);
+        if speed == settings.autopilot_speed() {
+            speeds.set_selection(index);
+        }
+    }
+
+    let form = LinearLayout::vertical()
+        .child(setting(
+            "Suit glyphs",
+            suit_glyphs.with_name("settings-suit-glyphs"),
+        ))
+        .child(setting("Theme", themes.with_name("settings-theme")))
+        .child(setting(
+            "Animations",
+            Checkbox::new()
+                .with_checked(settings.animations())
+                .with_name("settings-animations"),
+        ))
+        .child(setting(
+            "Auto-eliminate",
+            Checkbox::new()
+                .with_checked(settings.auto_eliminate())
+                .with_name("settings-auto-eliminate"),
+        ))
+        .child(setting(
+            "Confirm abandon",
+            Checkbox::new()
+                .with_checked(settings.confirm_abandon())
+                .with_name("settings-confirm-abandon"),
+        ))
+        .child(setting(
+            "Autopilot",
+            strategies.with_name("settings-autopilot-strategy"),
+        ))
+        .child(setting(
+            "Autopilot speed",
+            speeds.with_name("settings-autopilot-speed"),
+        ))
+        .child(setting(
+            "History file",
+            EditView::new()
+                .content(settings.history_path().to_string_lossy())
+                .with_name("settings-history-path")
+                .min_width(24),
+        ));
+
+    let settings = settings.clone();
+
+    s.add_layer(
+        Dialog::around(form)
+            .title("Settings")
+            .button("Save", move |s| {
+                let settings = match read_form(s, &settings) {
+                    Some(settings) => settings,
+                    None => {
+                        s.add_layer(Dialog::info("The history file needs a name."));
+                        return;
+                    }
+                };
+
+                if let Err(error) = settings.save(settings::DEFAULT_PATH) {
+                    s.add_layer(Dialog::info(format!(
+                        "The settings could not be saved: {}",
+                        error
+                    )));
+                    return;
+                }
+                s.pop_layer();
+                on_save(s, &settings);
+            })
+            .dismiss_button("Cancel"),
+    );
+}
+
+/// A line of the settings dialog with `label` in front of `view`.
+fn setting<V: View>(label: &str, view: V) -> LinearLayout
+{
+    LinearLayout::horizontal()
+        .child(TextView::new(label).fixed_width(LABEL_WIDTH))
+        .child(view)
+}
+
+/// `settings` with the values chosen in the dialog, or nothing if the history file is left empty.
+fn read_form(s: &mut Cursive, settings: &Settings) -> Option<Settings>
+{
+    let history_path = s
+        .call_on_name("settings-history-path", |view: &mut EditView| {
+            view.get_content()
+        })?
+        .trim()
+        .to_owned();
+    if history_path.is_empty() {
+        return None;
+    }
+
+    let mut settings = settings.clone();
+    settings.set_history_path(PathBuf::from(history_path));
+
+    if let Some(glyphs) = s
+        .call_on_name(
+            "settings-suit-glyphs",
+            |view: &mut SelectView<SuitGlyphs>| view.selection(),
+        )
+        .flatten()
+    {
+        settings.set_suit_glyphs(*glyphs);
+    }
+    if let Some(name) = s
+        .call_on_name("settings-theme", |view: &mut SelectView<Option<String>>| {
+            view.selection()
+        })
+        .flatten()
+    {
+        settings.set_theme((*name).clone());
+    }
+
+    if let Some(strategy) = s
+        .call_on_name(
+            "settings-autopilot-strategy",
+            |view: &mut SelectView<Strategy>| view.selection(),
+        )
+        .flatten()
+    {
+        settings.set_autopilot_strategy(*strategy);
+    }
+    if let Some(speed) = s
+        .call_on_name(
+            "settings-autopilot-speed",
+            |view: &mut SelectView<AutopilotSpeed>| view.selection(),
+        )
+        .flatten()
+    {
+        settings.set_autopilot_speed(*speed);
+    }
+
+    let checked = |s: &mut Cursive, name: &str| {
+        s.call_on_name(name, |view: &mut Checkbox| view.is_checked())
+            .unwrap_or(false)
+    };
+    settings
+        .set_animations(checked(s, "settings-animations"))
+        .set_auto_eliminate(checked(s, "settings-auto-eliminate"))
+        .set_confirm_abandon(checked(s, "settings-confirm-abandon"));
+
+    Some(settings)
+}
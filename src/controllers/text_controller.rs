@@ -0,0 +1,520 @@
+use std::{
+    cell::RefCell,
+    rc::{
+        Rc,
+        Weak,
+    },
+};
+
+use cursive::{
+    event,
+    view::{
+        Nameable,
+        Resizable,
+        ScrollStrategy,
+        Scrollable,
+    },
+    views::{
+        LinearLayout,
+        TextView,
+    },
+    Cursive,
+};
+
+use super::{
+    autopilot_controller::AutopilotController,
+    settings_controller::change_settings,
+    view_controller::{
+        abandon_game,
+        choose_row,
+        help_text,
+        show_help,
+        show_statistics,
+        toggle_placing,
+    },
+    Mode,
+};
+use crate::{
+    config::{
+        Action,
+        Keymap,
+        Settings,
+    },
+    controllers::ModelController,
+    game::{
+        Event,
+        GameResult,
+        Move,
+        RowIndex,
+        Table,
+    },
+};
+
+/// What the text controller knows about the game, kept up to date by the model controller.
+struct State
+{
+    table: Table,
+    deck_size: usize,
+
+    /// Whether the keys of the rows eliminate or choose the rows of a placement.
+    mode: Mode,
+
+    /// The row to place a card from, once it has been chosen.
+    from_index: Rc<RefCell<Option<RowIndex>>>,
+}
+
+/// Plays the game as a transcript of lines of text, which unlike the table can be followed with a
+/// screen reader. Every move, error and the table after each change are described in words.
+pub struct TextController
+{
+    _model_controller: Rc<RefCell<ModelController>>,
+    _state: Rc<RefCell<State>>,
+    _autopilot: Rc<RefCell<AutopilotController>>,
+}
+
+/// The amount of times per second the autopilot checks if it is time for the next move.
+static REFRESH_RATE: u32 = 4;
+
+impl TextController
+{
+    pub fn new(s: &mut Cursive, keymap: &Keymap, settings: &Settings) -> TextController
+    {
+        let model_controller = Rc::new(RefCell::new(ModelController::new()));
+        let state = Rc::new(RefCell::new(State {
+            table: Default::default(),
+            deck_size: 0,
+            mode: Mode::Eliminate,
+            from_index: Rc::new(RefCell::new(None)),
+        }));
+        let keymap = Rc::new(keymap.clone());
+        let settings = Rc::new(RefCell::new(settings.clone()));
+
+        model_controller
+            .borrow_mut()
+            .set_history_path(settings.borrow().history_path().clone())
+            .set_auto_eliminate(settings.borrow().auto_eliminate());
+
+        let autopilot = Rc::new(RefCell::new(AutopilotController::new(
+            Rc::downgrade(&model_controller),
+            &settings.borrow(),
+        )));
+
+        s.add_fullscreen_layer(
+            LinearLayout::vertical()
+                .child(
+                    TextView::new("")
+                        .with_name("transcript")
+                        .scrollable()
+                        .scroll_strategy(ScrollStrategy::StickToBottom)
+                        .full_height(),
+                )
+                .full_screen(),
+        );
+
+        //
+        // Set up global keys.
+        //
+        for (action, binding) in keymap.bindings() {
+            let model_controller = Rc::downgrade(&model_controller);
+            let state = Rc::downgrade(&state);
+            let keymap = Rc::clone(&keymap);
+            let settings = Rc::clone(&settings);
+            let autopilot = Rc::downgrade(&autopilot);
+
+            s.add_global_callback(binding.event().clone(), move |s| {
+                perform(
+                    s,
+                    action,
+                    &model_controller,
+                    &state,
+                    &keymap,
+                    &settings,
+                    &autopilot,
+                );
+            });
+        }
+
+        //
+        // Notice when the application was suspended and let the autopilot move unless a dialog is
+        // shown.
+        //
+        s.set_fps(REFRESH_RATE);
+        s.add_global_callback(event::Event::Refresh, {
+            let model_controller = Rc::downgrade(&model_controller);
+            let autopilot = Rc::downgrade(&autopilot);
+
+            move |s| {
+                if let Some(model_controller) = model_controller.upgrade() {
+                    model_controller.borrow_mut().on_refresh();
+                }
+                if let Some(autopilot) = autopilot.upgrade().filter(|_| s.screen().len() == 1) {
+                    autopilot.borrow_mut().on_refresh(s);
+                }
+            }
+        });
+
+        //
+        // Set up model controller lambdas.
+        //
+        let mut controller = model_controller.borrow_mut();
+
+        controller.set_on_event(Some(Box::new({
+            let state = Rc::downgrade(&state);
+
+            move |s, event| {
+                if let Some(state) = state.upgrade() {
+                    let line = state.borrow_mut().apply(event);
+                    say(s, line);
+                }
+            }
+        })));
+        controller.set_on_new_game(Some(Box::new({
+            let state = Rc::downgrade(&state);
+
+            move |s| {
+                if let Some(state) = state.upgrade() {
+                    let mut state = state.borrow_mut();
+                    state.table = Default::default();
+                    state.mode = Mode::Eliminate;
+                    state.from_index.borrow_mut().take();
+                }
+                say(s, "New game.");
+            }
+        })));
+        controller.set_on_table_changed(Some(Box::new({
+            let state = Rc::downgrade(&state);
+
+            move |s, table| {
+                if let Some(state) = state.upgrade() {
+                    let mut state = state.borrow_mut();
+                    state.table = table.clone();
+                    state.mode = Mode::Eliminate;
+                    state.from_index.borrow_mut().take();
+                }
+                say(s, "The last move was taken back.");
+            }
+        })));
+        controller.set_on_deck_size_changed(Some(Box::new({
+            let state = Rc::downgrade(&state);
+
+            move |_, len| {
+                if let Some(state) = state.upgrade() {
+                    state.borrow_mut().deck_size = len;
+                }
+            }
+        })));
+        controller.set_on_legal_moves_changed(Some(Box::new({
+            let state = Rc::downgrade(&state);
+
+            move |s, moves| {
+                if let Some(state) = state.upgrade() {
+                    let description = state.borrow().describe(moves);
+                    say(s, description);
+                }
+            }
+        })));
+        controller.set_on_error(Some(Box::new(|s, error| say(s, error.to_string()))));
+        controller.set_on_hint(Some(Box::new(|s, hint| match hint {
+            Some(hint) => say(s, format!("Hint: {}.", hint)),
+            None => say(s, "There are no possible moves."),
+        })));
+        controller.set_on_game_over(Some(Box::new(|s, result| {
+            say(
+                s,
+                match result {
+                    GameResult::Win => "Game over. You solved the idiot!",
+                    GameResult::Lose => "Game over. You did not solve the idiot this time.",
+                },
+            )
+        })));
+        drop(controller);
+
+        //
+        // Prepare game.
+        //
+        say(
+            s,
+            "Screen reader mode. Every move is described on a new line. Open the help to hear the \
+             keys.",
+        );
+        model_controller.borrow_mut().new_game(s);
+
+        TextController {
+            _model_controller: model_controller,
+            _state: state,
+            _autopilot: autopilot,
+        }
+    }
+}
+
+impl State
+{
+    /// Updates the table with `event` and describes what happened.
+    fn apply(&mut self, event: &Event) -> String
+    {
+        match event {
+            Event::Deal(cards) => {
+                for (row, card) in self.table.iter_mut().zip(cards) {
+                    row.push(card.clone());
+                }
+                format!(
+                    "Dealt {}.",
+                    cards
+                        .iter()
+                        .map(|card| card.name())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            }
+            Event::Eliminate(index) => match self.table[*index].pop() {
+                Some(card) => format!("Eliminated {} from row {}.", card.name(), index + 1),
+                None => format!("Eliminated a card from row {}.", index + 1),
+            },
+            Event::Place(from, to) => {
+                let card = self.table[*from].pop();
+                let line = match &card {
+                    Some(card) => {
+                        format!(
+                            "Placed {} from row {} in row {}.",
+                            card.name(),
+                            from + 1,
+                            to + 1
+                        )
+                    }
+                    None => format!("Placed a card from row {} in row {}.", from + 1, to + 1),
+                };
+
+                self.table[*to].extend(card);
+                self.mode = Mode::Eliminate;
+                line
+            }
+        }
+    }
+
+    /// Describes every row, the deck and the moves that can be made.
+    fn describe(&self, moves: &[Move]) -> String
+    {
+        let mut lines: Vec<String> = self
+            .table
+            .iter()
+            .enumerate()
+            .map(|(index, row)| match row.as_slice() {
+                [] => format!("Row {}: empty.", index + 1),
+                [card] => format!("Row {}: 1 card, {}.", index + 1, card.name()),
+                [.., card] => {
+                    format!(
+                        "Row {}: {} cards, bottom {}.",
+                        index + 1,
+                        row.len(),
+                        card.name()
+                    )
+                }
+            })
+            .collect();
+
+        lines.push(format!("Deck: {} cards.", self.deck_size));
+        if moves.is_empty() {
+            lines.push(String::from("No moves are possible."));
+        } else {
+            lines.push(format!(
+                "Possible moves: {}.",
+                moves
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+        lines.join("\n")
+    }
+}
+
+/// Adds a line to the end of the transcript.
+fn say<S: Into<String>>(s: &mut Cursive, line: S)
+{
+    let line = line.into();
+
+    s.call_on_name("transcript", |view: &mut TextView| {
+        view.append(format!("{}\n", line))
+    });
+}
+
+/// Performs `action`. While placing, the keys of the rows choose the row to move a card from and
+/// then the empty row to move it to. Nothing but quitting is done while a dialog is shown.
+fn perform(
+    s: &mut Cursive,
+    action: Action,
+    model_controller: &Weak<RefCell<ModelController>>,
+    state: &Weak<RefCell<State>>,
+    keymap: &Keymap,
+    settings: &Rc<RefCell<Settings>>,
+    autopilot: &Weak<RefCell<AutopilotController>>,
+)
+{
+    if s.screen().len() > 1 && action != Action::Quit {
+        return;
+    }
+
+    let (model_controller_rc, state) = match (model_controller.upgrade(), state.upgrade()) {
+        (Some(model_controller), Some(state)) => (model_controller, state),
+        _ => return,
+    };
+
+    match action {
+        Action::Deal => model_controller_rc.borrow_mut().deal(s),
+        Action::Place => {
+            let mode = state.borrow().mode.clone();
+            let next = toggle_placing(s, &mode, model_controller);
+            if mode != Mode::Eliminate {
+                say(s, "Placing cancelled.");
+            }
+            set_mode(s, &state, next);
+        }
+        Action::EliminateRow(index) => {
+            let (mode, from_index) = {
+                let state = state.borrow();
+                (state.mode.clone(), Rc::downgrade(&state.from_index))
+            };
+            let next = choose_row(s, index, &mode, model_controller, &from_index);
+            set_mode(s, &state, next);
+        }
+        Action::NewGame | Action::Quit => {
+            let confirm = settings.borrow().confirm_abandon();
+            abandon_game(s, action, model_controller, confirm)
+        }
+        Action::Undo => model_controller_rc.borrow_mut().undo(s),
+        Action::Hint => model_controller_rc.borrow().hint(s),
+        Action::Stats => show_statistics(s, settings.borrow().history_path()),
+        Action::History => say(s, "Replays are not available in screen reader mode."),
+        Action::Settings => change_settings(s, settings, model_controller, autopilot, |s, _| {
+            say(s, "Settings saved.")
+        }),
+        Action::Autopilot => {
+            if let Some(autopilot) = autopilot.upgrade() {
+                autopilot.borrow_mut().toggle();
+                if autopilot.borrow().is_running() {
+                    say(
+                        s,
+                        "Autopilot on. The computer plays until you hit the key again.",
+                    );
+                } else {
+                    say(s, "Autopilot off. It is your turn.");
+                }
+            }
+        }
+        Action::Help => show_help(s, &help_text(keymap, settings.borrow().suit_glyphs())),
+        // The transcript always reads out every card of a row.
+        Action::ShowRow
+        | Action::FocusLeft
+        | Action::FocusRight
+        | Action::FocusUp
+        | Action::FocusDown => (),
+    }
+}
+
+/// Continues in `mode` and tells which row to choose next if a placement goes on.
+fn set_mode(s: &mut Cursive, state: &RefCell<State>, mode: Mode)
+{
+    let previous = std::mem::replace(&mut state.borrow_mut().mode, mode.clone());
+
+    if mode != previous && mode != Mode::Eliminate {
+        say(s, format!("{}.", mode));
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::game::card::{
+        Card,
+        Rank,
+        Suit,
+    };
+
+    fn state(table: Table) -> State
+    {
+        State {
+            table,
+            deck_size: 40,
+            mode: Mode::Eliminate,
+            from_index: Rc::new(RefCell::new(None)),
+        }
+    }
+
+    fn card(suit: Suit, rank: Rank) -> Card
+    {
+        Card::new(suit, rank)
+    }
+
+    #[test]
+    fn describe_reads_every_row()
+    {
+        let state = state([
+            vec![
+                card(Suit::Clubs, Rank::Num(2)),
+                card(Suit::Spades, Rank::Num(5)),
+                card(Suit::Hearts, Rank::Queen),
+            ],
+            vec![],
+            vec![card(Suit::Hearts, Rank::King)],
+            vec![],
+        ]);
+
+        assert_eq!(
+            state.describe(&[Move::Eliminate(0), Move::Deal]),
+            "Row 1: 3 cards, bottom Queen of Hearts.\n\
+             Row 2: empty.\n\
+             Row 3: 1 card, King of Hearts.\n\
+             Row 4: empty.\n\
+             Deck: 40 cards.\n\
+             Possible moves: Eliminate the card in row 1, Deal new cards."
+        );
+        assert!(state.describe(&[]).ends_with("\nNo moves are possible."));
+    }
+
+    #[test]
+    fn apply_updates_the_table()
+    {
+        let mut state = state(Default::default());
+        let cards = [
+            card(Suit::Spades, Rank::Ace),
+            card(Suit::Spades, Rank::King),
+            card(Suit::Clubs, Rank::Num(7)),
+            card(Suit::Diamonds, Rank::Knight),
+        ];
+
+        assert_eq!(
+            state.apply(&Event::Deal(cards.clone())),
+            "Dealt Ace of Spades, King of Spades, 7 of Clubs, Knight of Diamonds."
+        );
+        assert_eq!(
+            state.apply(&Event::Eliminate(0)),
+            "Eliminated Ace of Spades from row 1."
+        );
+        assert!(state.table[0].is_empty());
+    }
+
+    #[test]
+    fn apply_describes_place_and_ends_placing()
+    {
+        let mut state = state([
+            vec![],
+            vec![
+                card(Suit::Clubs, Rank::Num(3)),
+                card(Suit::Spades, Rank::King),
+            ],
+            vec![],
+            vec![],
+        ]);
+        state.mode = Mode::PlaceTo;
+
+        assert_eq!(
+            state.apply(&Event::Place(1, 0)),
+            "Placed King of Spades from row 2 in row 1."
+        );
+        assert_eq!(state.table[0], vec![card(Suit::Spades, Rank::King)]);
+        assert_eq!(state.table[1].len(), 1);
+        assert_eq!(state.mode, Mode::Eliminate);
+    }
+}
@@ -1,13 +1,30 @@
 use std::{
     cell::RefCell,
+    collections::BTreeMap,
+    fmt::{
+        self,
+        Display,
+        Write,
+    },
     iter::zip,
-    rc::Rc,
+    path::Path,
+    rc::{
+        Rc,
+        Weak,
+    },
 };
 
+use chrono::Duration;
 use cursive::{
+    direction::Direction,
+    event::{
+        self,
+        Key,
+    },
     view::{
         Nameable,
         Resizable,
+        Scrollable,
     },
     views::{
         Button,
@@ -15,20 +32,46 @@ use cursive::{
         DummyView,
         LinearLayout,
         NamedView,
+        TextView,
     },
     Cursive,
+    View,
 };
 
+use super::{
+    autopilot_controller::AutopilotController,
+    settings_controller::change_settings,
+};
 use crate::{
-    controllers::ModelController,
+    archive,
+    config::{
+        settings::SuitGlyphs,
+        Action,
+        Keymap,
+        Settings,
+    },
+    controllers::{
+        ModelController,
+        ReplayController,
+    },
     game::{
+        card::Suit,
         Event,
         GameResult,
+        Move,
         RowIndex,
     },
+    statistics::{
+        format_average,
+        format_rate,
+        Statistics,
+    },
     views::{
+        Animation,
+        DiscardView,
         HighlightView,
         RowView,
+        CARDS_DIALOG,
     },
 };
 
@@ -37,6 +80,9 @@ use crate::{
 /// is a type missmatch.
 type PlaceButton = HighlightView<NamedView<Button>>;
 
+/// The type of view the autopilot button has, see `PlaceButton`.
+type AutopilotButton = HighlightView<Button>;
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum Mode
 {
@@ -45,20 +91,56 @@ pub enum Mode
     PlaceTo,
 }
 
+impl Display for Mode
+{
+    /// Writes what the player is expected to do in the mode.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self {
+            Mode::Eliminate => write!(f, "Eliminate"),
+            Mode::PlaceFrom => write!(f, "Place: select a row to move from"),
+            Mode::PlaceTo => write!(f, "Place: select an empty row"),
+        }
+    }
+}
+
 pub struct ViewController
 {
     _model_controller: Rc<RefCell<ModelController>>,
     _from_index: Rc<RefCell<Option<RowIndex>>>,
+    _autopilot: Rc<RefCell<AutopilotController>>,
 }
 
 static ROW_NAMES: [&str; 4] = ["row-0", "row-1", "row-2", "row-3"];
 
+/// The amount of times per second the screen is refreshed, which is what makes the clock tick.
+static REFRESH_RATE: u32 = 4;
+
+/// The amount of times per second the screen is refreshed while cards are animated.
+static ANIMATION_RATE: u32 = 30;
+
+/// The amount of games listed in the statistics dialog.
+static RECENT_GAMES: usize = 8;
+
 impl ViewController
 {
-    pub fn new(s: &mut Cursive) -> ViewController
+    pub fn new(s: &mut Cursive, keymap: &Keymap, settings: &Settings) -> ViewController
     {
+        let suit_glyphs = settings.suit_glyphs();
         let model_controller = Rc::new(RefCell::new(ModelController::new()));
         let from_index = Rc::new(RefCell::new(None));
+        let keymap = Rc::new(keymap.clone());
+        let settings = Rc::new(RefCell::new(settings.clone()));
+
+        model_controller
+            .borrow_mut()
+            .set_history_path(settings.borrow().history_path().clone())
+            .set_auto_eliminate(settings.borrow().auto_eliminate());
+
+        let autopilot = Rc::new(RefCell::new(AutopilotController::new(
+            Rc::downgrade(&model_controller),
+            &settings.borrow(),
+        )));
 
         //
         // Initialize RowViews.
@@ -69,126 +151,319 @@ impl ViewController
             let model_controller = Rc::downgrade(&model_controller);
             let from_index = Rc::downgrade(&from_index);
 
-            rows.add_child(
-                RowView::new()
-                    .with_on_action(move |s, mode| {
-                        mode.map(|mode| match mode {
-                            Mode::Eliminate => {
-                                model_controller.upgrade().map(|model_controller| {
-                                    model_controller.borrow_mut().eliminate(s, index)
-                                });
-                            }
-                            Mode::PlaceFrom => {
-                                from_index.upgrade().map(|from_index| {
-                                    let _ = from_index.borrow_mut().insert(index);
-                                });
-                                set_mode(s, Mode::PlaceTo);
-                            }
-                            Mode::PlaceTo => {
-                                from_index.upgrade().map(|from_index| {
-                                    from_index.borrow_mut().take().map(|from_index| {
-                                        model_controller.upgrade().map(|model_controller| {
-                                            model_controller
-                                                .borrow_mut()
-                                                .place(s, from_index, index);
-                                        })
-                                    })
-                                });
-                                set_mode(s, Mode::Eliminate);
-                            }
-                        });
-                    })
-                    .with_name(name)
-                    .fixed_width(6),
-            )
+            let row = RowView::new()
+                .with_card_style(settings.borrow().card_style())
+                .with_suit_glyphs(suit_glyphs)
+                .with_on_action(move |s, mode| {
+                    if let Some(mode) = mode {
+                        act_on_row(s, index, mode, &model_controller, &from_index);
+                    }
+                });
+            let width = row.width();
+
+            rows.add_child(row.with_name(name).fixed_width(width))
         }
 
         //
         // Add screen to cursive.
         //
         s.add_layer(
-            LinearLayout::horizontal()
-                .child(rows)
-                .child(DummyView.fixed_width(1))
+            LinearLayout::vertical()
                 .child(
-                    LinearLayout::vertical()
-                        .child({
-                            let model_controller = Rc::downgrade(&model_controller);
-
-                            Button::new("Deck[-]", move |s| {
-                                model_controller
-                                    .upgrade()
-                                    .map(|model_controller| model_controller.borrow_mut().deal(s));
-                            })
-                            .with_name("deck-button")
-                        })
+                    LinearLayout::horizontal()
+                        .child(rows)
+                        .child(DummyView.fixed_width(1))
                         .child(
-                            // TODO:
-                            //   If the button is disabled after a place and the player moved the
-                            //   focus to the buttons, the focus will end up at the view
-                            //   'new-game-button'. It would be preferable if it focused the view
-                            //   'deck-button' instead.
-                            HighlightView::new(
-                                Button::new("Place", |_| {}).with_name("place-button"),
-                            )
-                            .with_name("place-button-hightlight")
-                                as NamedView<PlaceButton>,
-                        )
-                        .child(DummyView)
-                        .child({
-                            let model_controller = Rc::downgrade(&model_controller);
-
-                            Button::new("New Game", move |s| {
-                                model_controller.upgrade().map(|model_controller| {
-                                    model_controller.borrow_mut().new_game(s)
-                                });
-                            })
-                        })
-                        .child({
-                            let model_controller = Rc::downgrade(&model_controller);
-
-                            Button::new("Quit", move |s| {
-                                model_controller.upgrade().map(|model_controller| {
-                                    model_controller.borrow_mut().drop_game()
-                                });
-                                s.quit();
-                            })
-                        })
-                        .child(DummyView.fixed_height(1)),
-                ),
+                            LinearLayout::vertical()
+                                .child({
+                                    let model_controller = Rc::downgrade(&model_controller);
+
+                                    Button::new("Deck[-]", move |s| {
+                                        model_controller.upgrade().map(|model_controller| {
+                                            model_controller.borrow_mut().deal(s)
+                                        });
+                                    })
+                                    .with_name("deck-button")
+                                })
+                                .child(
+                                    HighlightView::new(
+                                        Button::new("Place", |_| {}).with_name("place-button"),
+                                    )
+                                    .with_name("place-button-hightlight")
+                                        as NamedView<PlaceButton>,
+                                )
+                                .child({
+                                    let model_controller = Rc::downgrade(&model_controller);
+
+                                    Button::new("Undo", move |s| {
+                                        if let Some(model_controller) = model_controller.upgrade() {
+                                            model_controller.borrow_mut().undo(s);
+                                        }
+                                    })
+                                })
+                                .child({
+                                    let autopilot = Rc::clone(&autopilot);
+
+                                    HighlightView::new(Button::new("Autopilot", move |s| {
+                                        toggle_autopilot(s, &autopilot)
+                                    }))
+                                    .with_name("autopilot-button-highlight")
+                                        as NamedView<AutopilotButton>
+                                })
+                                .child(DummyView)
+                                .child({
+                                    let model_controller = Rc::downgrade(&model_controller);
+                                    let settings = Rc::clone(&settings);
+
+                                    Button::new("New Game", move |s| {
+                                        let confirm = settings.borrow().confirm_abandon();
+                                        abandon_game(s, Action::NewGame, &model_controller, confirm)
+                                    })
+                                    .with_name("new-game-button")
+                                })
+                                .child({
+                                    let settings = Rc::clone(&settings);
+
+                                    Button::new("Stats", move |s| {
+                                        let path = settings.borrow().history_path().clone();
+                                        show_statistics(s, &path)
+                                    })
+                                })
+                                .child({
+                                    let settings = Rc::clone(&settings);
+
+                                    Button::new("History", move |s| {
+                                        let settings = settings.borrow().clone();
+                                        ReplayController::show_archive(s, &settings)
+                                    })
+                                })
+                                .child({
+                                    let model_controller = Rc::downgrade(&model_controller);
+                                    let settings = Rc::clone(&settings);
+                                    let autopilot = Rc::downgrade(&autopilot);
+
+                                    Button::new("Settings", move |s| {
+                                        change_settings(
+                                            s,
+                                            &settings,
+                                            &model_controller,
+                                            &autopilot,
+                                            set_suit_glyphs,
+                                        )
+                                    })
+                                })
+                                .child({
+                                    let keymap = Rc::clone(&keymap);
+                                    let settings = Rc::clone(&settings);
+
+                                    Button::new("Help", move |s| {
+                                        let suit_glyphs = settings.borrow().suit_glyphs();
+                                        show_help(s, &help_text(&keymap, suit_glyphs))
+                                    })
+                                })
+                                .child({
+                                    let model_controller = Rc::downgrade(&model_controller);
+                                    let settings = Rc::clone(&settings);
+
+                                    Button::new("Quit", move |s| {
+                                        let confirm = settings.borrow().confirm_abandon();
+                                        abandon_game(s, Action::Quit, &model_controller, confirm)
+                                    })
+                                })
+                                .child(DummyView.fixed_height(1)),
+                        ),
+                )
+                .child(
+                    DiscardView::new()
+                        .with_suit_glyphs(suit_glyphs)
+                        .with_name("discard-pile"),
+                )
+                .child(
+                    LinearLayout::horizontal()
+                        .child(TextView::new("").with_name("status-clock"))
+                        .child(DummyView.fixed_width(3))
+                        .child(TextView::new("").with_name("status-discarded"))
+                        .child(DummyView.fixed_width(3))
+                        .child(TextView::new("").with_name("status-mode")),
+                )
+                .with_name("table"),
         );
 
+        //
+        // Set up global keys.
+        //
+        for (action, binding) in keymap.bindings() {
+            let model_controller = Rc::downgrade(&model_controller);
+            let from_index = Rc::downgrade(&from_index);
+            let keymap = Rc::clone(&keymap);
+            let settings = Rc::clone(&settings);
+            let autopilot = Rc::downgrade(&autopilot);
+
+            s.add_global_callback(binding.event().clone(), move |s| {
+                perform(
+                    s,
+                    action,
+                    &model_controller,
+                    &from_index,
+                    &keymap,
+                    &settings,
+                    &autopilot,
+                );
+            });
+        }
+
+        //
+        // Tick the clock in the status bar, slow down again once animations are over and let the
+        // autopilot move unless a dialog is shown.
+        //
+        s.set_fps(REFRESH_RATE);
+        s.add_global_callback(event::Event::Refresh, {
+            let model_controller = Rc::downgrade(&model_controller);
+            let autopilot = Rc::downgrade(&autopilot);
+
+            move |s| {
+                if let Some(model_controller) = model_controller.upgrade() {
+                    model_controller.borrow_mut().on_refresh();
+                }
+                if let Some(autopilot) = autopilot.upgrade() {
+                    if s.screen().len() == 1 {
+                        autopilot.borrow_mut().on_refresh(s);
+                    }
+                    set_autopilot_highlighted(s, autopilot.borrow().is_running());
+                }
+
+                let animating = ROW_NAMES.iter().any(|name| {
+                    s.call_on_name(name, |view: &mut RowView| view.is_animating())
+                        .unwrap_or(false)
+                });
+                if !animating {
+                    s.set_fps(REFRESH_RATE);
+                }
+
+                if let Some(elapsed) = model_controller
+                    .upgrade()
+                    .and_then(|model_controller| model_controller.borrow().elapsed())
+                {
+                    s.call_on_name("status-clock", |view: &mut TextView| {
+                        view.set_content(format_clock(elapsed))
+                    });
+                }
+            }
+        });
+
         //
         // Set up model controller lambdas.
         //
-        model_controller
-            .borrow_mut()
-            .set_on_event(Some(Box::new(move |s, event| match event {
-                Event::Deal(cards) => {
-                    for (name, card) in zip(ROW_NAMES, cards) {
-                        s.call_on_name(name, |view: &mut RowView| view.push(card.clone()));
+        model_controller.borrow_mut().set_on_event(Some(Box::new({
+            let settings = Rc::clone(&settings);
+
+            move |s, event| {
+                let animations = settings.borrow().animations();
+
+                match event {
+                    Event::Deal(cards) => {
+                        for (order, (name, card)) in zip(ROW_NAMES, cards).enumerate() {
+                            s.call_on_name(name, |view: &mut RowView| {
+                                view.push(card.clone());
+                                if animations {
+                                    view.animate(Animation::Deal {
+                                        order: order as u32,
+                                    });
+                                }
+                            });
+                        }
+                    }
+                    Event::Eliminate(index) => {
+                        s.call_on_name(ROW_NAMES[*index], |view: &mut RowView| {
+                            if let Some(card) = view.pop().filter(|_| animations) {
+                                view.animate(Animation::Eliminate { card });
+                            }
+                        });
+                    }
+                    Event::Place(from, to) => {
+                        if let Some((card, from_y)) = s
+                            .call_on_name(ROW_NAMES[*from], |view: &mut RowView| {
+                                view.pop().map(|card| (card, view.cards().len()))
+                            })
+                            .flatten()
+                        {
+                            let to_y = s
+                                .call_on_name(ROW_NAMES[*to], |view: &mut RowView| {
+                                    view.push(card.clone());
+                                    view.cards().len() - 1
+                                })
+                                .unwrap_or(0);
+
+                            if animations {
+                                for (column, name) in ROW_NAMES.iter().enumerate() {
+                                    let animation = Animation::Place {
+                                        card: card.clone(),
+                                        column: column as isize - *from as isize,
+                                        distance: *to as isize - *from as isize,
+                                        from_y,
+                                        to_y,
+                                    };
+                                    s.call_on_name(name, |view: &mut RowView| {
+                                        view.animate(animation)
+                                    });
+                                }
+                            }
+                        }
+                        set_mode(s, Mode::Eliminate);
                     }
                 }
-                Event::Eliminate(index) => {
-                    s.call_on_name(ROW_NAMES[*index], |view: &mut RowView| view.pop());
+                if animations {
+                    s.set_fps(ANIMATION_RATE);
                 }
-                Event::Place(from, to) => {
-                    if let Some(card) = s
-                        .call_on_name(ROW_NAMES[*from], |view: &mut RowView| view.pop())
-                        .flatten()
-                    {
-                        s.call_on_name(ROW_NAMES[*to], move |view: &mut RowView| view.push(card));
+            }
+        })));
+        model_controller
+            .borrow_mut()
+            .set_on_new_game(Some(Box::new({
+                let from_index = Rc::downgrade(&from_index);
+
+                move |s| {
+                    for name in ROW_NAMES {
+                        s.call_on_name(name, |view: &mut RowView| view.clear());
+                    }
+
+                    // A placement started in the previous game does not go on in the new one.
+                    if let Some(from_index) = from_index.upgrade() {
+                        from_index.borrow_mut().take();
                     }
                     set_mode(s, Mode::Eliminate);
                 }
             })));
         model_controller
             .borrow_mut()
-            .set_on_new_game(Some(Box::new(|s| {
-                for name in ROW_NAMES {
-                    s.call_on_name(name, |view: &mut RowView| view.clear());
+            .set_on_table_changed(Some(Box::new({
+                let from_index = Rc::downgrade(&from_index);
+
+                move |s, table| {
+                    for (name, cards) in zip(ROW_NAMES, table) {
+                        s.call_on_name(name, |view: &mut RowView| view.set_cards(cards.clone()));
+                    }
+
+                    // Any half made placement refers to a table that no longer exists.
+                    if let Some(from_index) = from_index.upgrade() {
+                        from_index.borrow_mut().take();
+                    }
+                    set_mode(s, Mode::Eliminate);
                 }
             })));
+        model_controller
+            .borrow_mut()
+            .set_on_error(Some(Box::new(|s, error| {
+                s.add_layer(Dialog::info(error.to_string()))
+            })));
+        model_controller
+            .borrow_mut()
+            .set_on_hint(Some(Box::new(|s, hint| {
+                s.add_layer(Dialog::info(match hint {
+                    Some(hint) => format!("{}.", hint),
+                    None => String::from("There are no possible moves."),
+                }))
+            })));
         model_controller
             .borrow_mut()
             .set_on_deck_size_changed(Some(Box::new(|s, len| {
@@ -196,13 +471,20 @@ impl ViewController
                     button.set_label(format!("Deck[{}]", len));
                 });
             })));
+        model_controller
+            .borrow_mut()
+            .set_on_discard_pile_changed(Some(Box::new(|s, discard_pile| {
+                s.call_on_name("status-discarded", |view: &mut TextView| {
+                    view.set_content(format!("Discarded: {}", discard_pile.len()))
+                });
+                s.call_on_name("discard-pile", |view: &mut DiscardView| {
+                    view.set_cards(discard_pile.clone())
+                });
+            })));
         model_controller
             .borrow_mut()
             .set_on_check_deal_possible(Some(Box::new(|s, possible| {
                 s.call_on_name("deck-button", |button: &mut Button| {
-                    // TODO:
-                    //   If the deck button is disabled after a deal the focus disappears. It would
-                    //   be preferable if it was moved to some row on the table.
                     if possible {
                         button.enable();
                     } else {
@@ -223,15 +505,21 @@ impl ViewController
             })));
         model_controller
             .borrow_mut()
-            .set_on_game_over(Some(Box::new(|s, result| match result {
-                // TODO:
-                //   When this dialog is dismissed it would be preferable if the focus was set on
-                //   the 'new-game' button.
-                GameResult::Win => s.add_layer(Dialog::info("You solved the idiot! Great job!")),
-                GameResult::Lose => s.add_layer(Dialog::info(
-                    "Game over, you did not solve the idiot this time.",
-                )),
+            .set_on_game_over(Some(Box::new(|s, result| {
+                let text = match result {
+                    GameResult::Win => "You solved the idiot! Great job!",
+                    GameResult::Lose => "Game over, you did not solve the idiot this time.",
+                };
+
+                // Starting a new game is the only thing left to do.
+                s.add_layer(Dialog::text(text).button("Ok", |s| {
+                    s.pop_layer();
+                    let _ = s.focus_name("new-game-button");
+                }));
             })));
+        model_controller
+            .borrow_mut()
+            .set_on_legal_moves_changed(Some(Box::new(refocus)));
 
         //
         // Prepare game.
@@ -242,6 +530,7 @@ impl ViewController
         ViewController {
             _model_controller: model_controller,
             _from_index: from_index,
+            _autopilot: autopilot,
         }
     }
 }
@@ -262,4 +551,507 @@ fn set_mode(s: &mut Cursive, mode: Mode)
     s.call_on_name("place-button-hightlight", |highlight: &mut PlaceButton| {
         highlight.set_highlighted(mode != Mode::Eliminate)
     });
+
+    s.call_on_name("status-mode", |view: &mut TextView| {
+        view.set_content(mode.to_string())
+    });
+
+    let targets: Vec<RowIndex> = (0..ROW_NAMES.len())
+        .filter(|&index| {
+            s.call_on_name(ROW_NAMES[index], |view: &mut RowView| view.is_target())
+                .unwrap_or(false)
+        })
+        .collect();
+
+    if mode == Mode::Eliminate {
+        // Any row that can be acted on keeps the focus until the next move.
+        move_focus(s, &targets, &[]);
+    } else if !focused_row(s).is_some_and(|index| targets.contains(&index)) {
+        // A row is what has to be selected next.
+        if let Some(&index) = targets.first() {
+            let _ = s.focus_name(ROW_NAMES[index]);
+        }
+    }
+}
+
+/// Moves the focus to the widget of the first move in `moves` if the focused widget can no longer
+/// be used, e.g. because it was disabled, or if it is a row no card can be eliminated from. The
+/// focus moves to a row a card can be eliminated from, the place button or the deck. If there is no
+/// legal move it moves to any row that can be acted on, the deck or else the new game button. A
+/// usable button keeps the focus so the player is not interrupted.
+fn refocus(s: &mut Cursive, moves: &[Move])
+{
+    let rows: Vec<RowIndex> = moves
+        .iter()
+        .filter_map(|legal_move| match legal_move {
+            Move::Eliminate(index) => Some(*index),
+            _ => None,
+        })
+        .collect();
+
+    let mut names: Vec<&str> = rows.iter().map(|&index| ROW_NAMES[index]).collect();
+    if moves
+        .iter()
+        .any(|legal_move| matches!(legal_move, Move::Place(..)))
+    {
+        names.push("place-button");
+    }
+    if moves.contains(&Move::Deal) {
+        names.push("deck-button");
+    }
+
+    move_focus(s, &rows, &names);
+}
+
+/// Moves the focus to the first view in `names` unless the focused view is usable. Only the rows in
+/// `rows` are usable among the rows.
+fn move_focus(s: &mut Cursive, rows: &[RowIndex], names: &[&'static str])
+{
+    let usable = s
+        .call_on_name("table", |table: &mut LinearLayout| focus_path(table).1)
+        .unwrap_or(false);
+    let on_other_row = focused_row(s).is_some_and(|index| !rows.contains(&index));
+    if usable && !on_other_row {
+        return;
+    }
+
+    let name = names
+        .first()
+        .copied()
+        .or_else(|| {
+            ROW_NAMES.iter().copied().find(|&name| {
+                s.call_on_name(name, |view: &mut RowView| view.is_target())
+                    .unwrap_or(false)
+            })
+        })
+        .or_else(|| {
+            s.call_on_name("deck-button", |button: &mut Button| button.is_enabled())
+                .unwrap_or(false)
+                .then_some("deck-button")
+        })
+        .unwrap_or("new-game-button");
+    let _ = s.focus_name(name);
+}
+
+/// Moves the focus on the table the way the arrow `key` does. The key is handled by the table
+/// alone, so the focus stays put at the edge of the table.
+fn shift_focus(s: &mut Cursive, key: Key)
+{
+    if let Some(result) = s.call_on_name("table", |table: &mut LinearLayout| {
+        table.on_event(event::Event::Key(key))
+    }) {
+        result.process(s);
+    }
+}
+
+/// Shows every card of the row with the focus, the same as clicking its buried cards, or closes
+/// the dialog with the cards again.
+fn toggle_expanded(s: &mut Cursive)
+{
+    if let Some(position) = s.screen_mut().find_layer_from_name(CARDS_DIALOG) {
+        s.screen_mut().remove_layer(position);
+        return;
+    }
+
+    if let Some(index) = focused_row(s).filter(|_| s.screen().len() == 1) {
+        if let Some(result) = s.call_on_name(ROW_NAMES[index], |row: &mut RowView| row.expand()) {
+            result.process(s);
+        }
+    }
+}
+
+/// The row with the focus, if the focus is on the table.
+fn focused_row(s: &mut Cursive) -> Option<RowIndex>
+{
+    let (path, _) = s.call_on_name("table", |table: &mut LinearLayout| focus_path(table))?;
+
+    // The rows are the first child of the first child of the table.
+    match path[..] {
+        [0, 0, index] => Some(index),
+        _ => None,
+    }
+}
+
+/// The indices of the focused children from `layout` down to the focused view, and whether that
+/// view still accepts the focus.
+fn focus_path(layout: &mut LinearLayout) -> (Vec<usize>, bool)
+{
+    let index = layout.get_focus_index();
+
+    let (mut path, usable) = match layout.get_child_mut(index) {
+        Some(child) => match child.downcast_mut::<LinearLayout>() {
+            Some(inner) => focus_path(inner),
+            None => (Vec::new(), child.take_focus(Direction::none())),
+        },
+        None => (Vec::new(), false),
+    };
+    path.insert(0, index);
+    (path, usable)
+}
+
+/// Formats `duration` as a clock, e.g. '05:09' or '1:05:09'.
+fn format_clock(duration: Duration) -> String
+{
+    let seconds = duration.num_seconds().max(0);
+
+    if seconds >= 3600 {
+        format!(
+            "{}:{:02}:{:02}",
+            seconds / 3600,
+            seconds % 3600 / 60,
+            seconds % 60
+        )
+    } else {
+        format!("{:02}:{:02}", seconds / 60, seconds % 60)
+    }
+}
+
+/// Acts on the row at `index` the way `mode` says, as if the row was selected.
+fn act_on_row(
+    s: &mut Cursive,
+    index: RowIndex,
+    mode: Mode,
+    model_controller: &Weak<RefCell<ModelController>>,
+    from_index: &Weak<RefCell<Option<RowIndex>>>,
+)
+{
+    let next = choose_row(s, index, &mode, model_controller, from_index);
+    if next != mode {
+        set_mode(s, next);
+    }
+}
+
+/// Eliminates from the row at `index` or takes it as the next row of a placement, depending on
+/// `mode`. The row a card is placed from is kept in `from_index` until the empty row is chosen.
+/// Returns the mode to continue in.
+pub(super) fn choose_row(
+    s: &mut Cursive,
+    index: RowIndex,
+    mode: &Mode,
+    model_controller: &Weak<RefCell<ModelController>>,
+    from_index: &Weak<RefCell<Option<RowIndex>>>,
+) -> Mode
+{
+    match mode {
+        Mode::Eliminate => {
+            model_controller
+                .upgrade()
+                .map(|model_controller| model_controller.borrow_mut().eliminate(s, index));
+            Mode::Eliminate
+        }
+        Mode::PlaceFrom => {
+            let possible = model_controller
+                .upgrade()
+                .map(|model_controller| model_controller.borrow().check_place_from(s, index))
+                .unwrap_or(false);
+
+            if possible {
+                from_index.upgrade().map(|from_index| {
+                    let _ = from_index.borrow_mut().insert(index);
+                });
+                Mode::PlaceTo
+            } else {
+                Mode::PlaceFrom
+            }
+        }
+        Mode::PlaceTo => {
+            from_index.upgrade().map(|from_index| {
+                from_index.borrow_mut().take().map(|from_index| {
+                    model_controller.upgrade().map(|model_controller| {
+                        model_controller.borrow_mut().place(s, from_index, index);
+                    })
+                })
+            });
+            Mode::Eliminate
+        }
+    }
+}
+
+/// Starts a placement if a card can be placed, or cancels the placement that is being made.
+/// Returns the mode to continue in.
+pub(super) fn toggle_placing(
+    s: &mut Cursive,
+    mode: &Mode,
+    model_controller: &Weak<RefCell<ModelController>>,
+) -> Mode
+{
+    let possible = *mode == Mode::Eliminate
+        && model_controller
+            .upgrade()
+            .is_some_and(|model_controller| model_controller.borrow().check_place_possible(s));
+
+    if possible {
+        Mode::PlaceFrom
+    } else {
+        Mode::Eliminate
+    }
+}
+
+/// Performs `action` the same way as if the player had used the corresponding widget. Nothing but
+/// quitting and closing the cards of a row is done while a dialog is shown on top of the table.
+fn perform(
+    s: &mut Cursive,
+    action: Action,
+    model_controller: &Weak<RefCell<ModelController>>,
+    from_index: &Weak<RefCell<Option<RowIndex>>>,
+    keymap: &Keymap,
+    settings: &Rc<RefCell<Settings>>,
+    autopilot: &Weak<RefCell<AutopilotController>>,
+)
+{
+    if s.screen().len() > 1 && !matches!(action, Action::Quit | Action::ShowRow) {
+        return;
+    }
+
+    let model_controller_rc = match model_controller.upgrade() {
+        Some(model_controller) => model_controller,
+        None => return,
+    };
+
+    match action {
+        Action::Deal => model_controller_rc.borrow_mut().deal(s),
+        Action::Place => {
+            if let Some(mode) = current_mode(s) {
+                let next = toggle_placing(s, &mode, model_controller);
+                if next != mode {
+                    set_mode(s, next);
+                }
+            }
+        }
+        Action::EliminateRow(index) => {
+            if let Some(mode) = current_mode(s) {
+                act_on_row(s, index, mode, model_controller, from_index);
+            }
+        }
+        Action::NewGame | Action::Quit => {
+            let confirm = settings.borrow().confirm_abandon();
+            abandon_game(s, action, model_controller, confirm)
+        }
+        Action::Undo => model_controller_rc.borrow_mut().undo(s),
+        Action::Hint => model_controller_rc.borrow().hint(s),
+        Action::Stats => show_statistics(s, settings.borrow().history_path()),
+        Action::History => {
+            let settings = settings.borrow().clone();
+            ReplayController::show_archive(s, &settings)
+        }
+        Action::Settings => {
+            change_settings(s, settings, model_controller, autopilot, set_suit_glyphs)
+        }
+        Action::Autopilot => {
+            if let Some(autopilot) = autopilot.upgrade() {
+                toggle_autopilot(s, &autopilot);
+            }
+        }
+        Action::Help => show_help(s, &help_text(keymap, settings.borrow().suit_glyphs())),
+        Action::ShowRow => toggle_expanded(s),
+        Action::FocusLeft => shift_focus(s, Key::Left),
+        Action::FocusRight => shift_focus(s, Key::Right),
+        Action::FocusUp => shift_focus(s, Key::Up),
+        Action::FocusDown => shift_focus(s, Key::Down),
+    }
+}
+
+/// Draws the cards with the suit glyphs of `settings`.
+fn set_suit_glyphs(s: &mut Cursive, settings: &Settings)
+{
+    for name in ROW_NAMES {
+        s.call_on_name(name, |view: &mut RowView| {
+            view.set_suit_glyphs(settings.suit_glyphs());
+        });
+    }
+    s.call_on_name("discard-pile", |view: &mut DiscardView| {
+        view.set_suit_glyphs(settings.suit_glyphs())
+    });
+}
+
+/// Starts or stops the autopilot and shows whether it is playing on its button.
+fn toggle_autopilot(s: &mut Cursive, autopilot: &Rc<RefCell<AutopilotController>>)
+{
+    autopilot.borrow_mut().toggle();
+    set_autopilot_highlighted(s, autopilot.borrow().is_running());
+}
+
+fn set_autopilot_highlighted(s: &mut Cursive, running: bool)
+{
+    s.call_on_name(
+        "autopilot-button-highlight",
+        |highlight: &mut AutopilotButton| highlight.set_highlighted(running),
+    );
+}
+
+/// Starts a new game or quits, depending on `action`. If `confirm` is set and the current game is
+/// not over yet, the player is asked first. Quitting again while being asked does not ask a second
+/// time.
+pub(super) fn abandon_game(
+    s: &mut Cursive,
+    action: Action,
+    model_controller: &Weak<RefCell<ModelController>>,
+    confirm: bool,
+)
+{
+    let model_controller_rc = match model_controller.upgrade() {
+        Some(model_controller) => model_controller,
+        None => return,
+    };
+
+    let asking = s.find_name::<Dialog>("confirm-abandon").is_some();
+    if confirm && !asking && model_controller_rc.borrow().is_undecided() {
+        let label = match action {
+            Action::Quit => "Quit",
+            _ => "New Game",
+        };
+        let model_controller = Weak::clone(model_controller);
+
+        s.add_layer(
+            Dialog::text(
+                "The current game is not over yet. It will be recorded as abandoned and can not \
+                 be continued later.",
+            )
+            .title("Abandon game?")
+            .button(label, move |s| {
+                s.pop_layer();
+                abandon_game(s, action, &model_controller, false);
+            })
+            .dismiss_button("Cancel")
+            .with_name("confirm-abandon"),
+        );
+        return;
+    }
+
+    match action {
+        Action::Quit => {
+            model_controller_rc.borrow_mut().drop_game();
+            s.quit();
+        }
+        _ => model_controller_rc.borrow_mut().new_game(s),
+    }
+}
+
+/// Shows the lifetime statistics of the games in the archive at `path`.
+pub(super) fn show_statistics(s: &mut Cursive, path: &Path)
+{
+    let archive = match archive::load(path) {
+        Ok(archive) => archive,
+        Err(error) => {
+            s.add_layer(Dialog::info(format!(
+                "The game history could not be read: {}",
+                error
+            )));
+            return;
+        }
+    };
+    let statistics = Statistics::new(archive.values());
+
+    let recent = statistics
+        .recent(RECENT_GAMES)
+        .iter()
+        .rev()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+
+    let text = [
+        format!("Games played:       {}", statistics.games_played()),
+        format!("Won:                {}", statistics.wins()),
+        format!("Lost:               {}", statistics.losses()),
+        format!("Abandoned:          {}", statistics.abandoned()),
+        format!("Win rate:           {}", format_rate(statistics.win_rate())),
+        format!("Current streak:     {}", statistics.current_streak()),
+        format!("Best streak:        {}", statistics.best_streak()),
+        format!(
+            "Average cards left: {}",
+            format_average(statistics.average_cards_left())
+        ),
+        String::new(),
+        String::from("Last games, newest first:"),
+        if recent.is_empty() {
+            String::from("-")
+        } else {
+            recent.join(", ")
+        },
+    ];
+
+    s.add_layer(
+        Dialog::around(TextView::new(text.join("\n")))
+            .title("Statistics")
+            .dismiss_button("Ok"),
+    );
+}
+
+pub(super) fn show_help(s: &mut Cursive, help: &str)
+{
+    s.add_layer(
+        Dialog::around(TextView::new(help).scrollable())
+            .title("Help")
+            .dismiss_button("Close")
+            .max_width(80),
+    );
+}
+
+/// The rules, the keys bound in `keymap` and the symbols of the suits.
+pub(super) fn help_text(keymap: &Keymap, suit_glyphs: SuitGlyphs) -> String
+{
+    let mut text = String::from(
+        "RULES\n\
+         \n\
+         The goal is to end up with only the four kings on the table, one in each row.\n\
+         \n\
+         Deck deals a card from the deck to the bottom of every row. Cards can only be dealt when \
+         no two rows end with cards of the same suit.\n\
+         \n\
+         If two rows end with cards of the same suit, the lesser card can be eliminated by \
+         selecting it. Aces are low and kings are high.\n\
+         \n\
+         If a row is empty, the bottom card of a row with two or more cards can be moved to it. \
+         Select Place, then the row to move the card from and then the empty row.\n\
+         \n\
+         Undo takes back the last move.\n\
+         \n\
+         KEYS\n\
+         \n",
+    );
+
+    let mut keys: BTreeMap<Action, Vec<&str>> = BTreeMap::new();
+    for (action, binding) in keymap.bindings() {
+        keys.entry(action).or_default().push(binding.name());
+    }
+    for (action, names) in keys {
+        writeln!(text, "{:<16} {}", names.join(", "), action.description()).unwrap();
+    }
+    writeln!(text, "{:<16} Show the debug console", "?").unwrap();
+    text.push_str("\nCards and buttons can also be selected with the mouse.\n\nSUITS\n\n");
+
+    for suit in Suit::all() {
+        writeln!(text, "{:<16} {:?}", suit_glyphs.suit(&suit), suit).unwrap();
+    }
+    text
+}
+
+/// The mode the rows are in.
+fn current_mode(s: &mut Cursive) -> Option<Mode>
+{
+    s.call_on_name(ROW_NAMES[0], |view: &mut RowView| view.mode().clone())
+        .flatten()
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn help_text_shows_rebound_keys()
+    {
+        let keymap = Keymap::parse(
+            r#"
+            [bindings]
+            deal = ["space"]
+            "#,
+        )
+        .unwrap();
+        let text = help_text(&keymap, SuitGlyphs::Ascii);
+        let description = Action::Deal.description();
+
+        assert!(text.contains(&format!("{:<16} {}\n", "space", description)));
+        assert!(!text.contains(&format!("{:<16} {}\n", "d", description)));
+    }
 }
@@ -0,0 +1,226 @@
+use std::{
+    fmt::Write,
+    iter::zip,
+};
+
+use chrono::Duration;
+use uuid::Uuid;
+
+use crate::{
+    archive::Archive,
+    game::{
+        analytics::Timings,
+        Event,
+        History,
+    },
+};
+
+/// Exports the archive as CSV with one row per game, oldest game first.
+pub fn csv(archive: &Archive) -> String
+{
+    let mut csv = String::from("id,start_date,duration_seconds,result,cards_left,moves,notation\n");
+
+    for (id, history) in sorted(archive) {
+        let fields = [
+            id.to_string(),
+            history
+                .start_date()
+                .map(|date| date.to_rfc3339())
+                .unwrap_or_default(),
+            duration(history)
+                .map(|duration| duration.num_seconds().to_string())
+                .unwrap_or_default(),
+            history.outcome().to_string(),
+            history.final_cards_left().to_string(),
+            history.events().len().to_string(),
+            history
+                .events()
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(" "),
+        ];
+
+        csv.push_str(
+            &fields
+                .iter()
+                .map(|field| csv_field(field))
+                .collect::<Vec<_>>()
+                .join(","),
+        );
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Exports the archive as a Markdown report with the moves of every game, oldest game first.
+pub fn markdown(archive: &Archive) -> String
+{
+    let mut markdown = String::from("# The Idiot - Game history\n");
+
+    for (id, history) in sorted(archive) {
+        let date = history
+            .start_date()
+            .map(|date| date.format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_else(|| String::from("Unknown date"));
+
+        writeln!(markdown, "\n## {} - {}\n", date, history.outcome()).unwrap();
+        writeln!(markdown, "- Id: `{}`", id).unwrap();
+        if let Some(duration) = duration(history) {
+            writeln!(markdown, "- Duration: {}", format_duration(duration)).unwrap();
+        }
+        writeln!(markdown, "- Cards left: {}", history.final_cards_left()).unwrap();
+        writeln!(markdown, "- Moves: {}", history.events().len()).unwrap();
+
+        if history.events().is_empty() {
+            continue;
+        }
+
+        markdown.push('\n');
+        match Timings::new(history) {
+            Some(timings) => {
+                for (index, (event, time)) in
+                    zip(timings.events(), timings.move_times()).enumerate()
+                {
+                    writeln!(
+                        markdown,
+                        "{}. {}, {}",
+                        index + 1,
+                        describe(history, index, event),
+                        format_duration(*time)
+                    )
+                    .unwrap();
+                }
+            }
+            None => {
+                for (index, event) in history.events().iter().enumerate() {
+                    writeln!(
+                        markdown,
+                        "{}. {}",
+                        index + 1,
+                        describe(history, index, event)
+                    )
+                    .unwrap();
+                }
+            }
+        }
+    }
+    markdown
+}
+
+/// Formats `duration` as minutes and seconds, e.g. '2m 05.3s'.
+pub fn format_duration(duration: Duration) -> String
+{
+    let milliseconds = duration.num_milliseconds();
+    let minutes = milliseconds / 60_000;
+    let seconds = (milliseconds % 60_000) as f64 / 1000.0;
+
+    if minutes > 0 {
+        format!("{}m {:04.1}s", minutes, seconds)
+    } else {
+        format!("{:.1}s", seconds)
+    }
+}
+
+/// `event`, the event at `index` in `history`, in the short notation followed by the card it
+/// eliminated or placed, e.g. '`E2` (Q<3)'. Deals already name their cards.
+fn describe(history: &History, index: usize, event: &Event) -> String
+{
+    let row = match event {
+        Event::Deal(_) => None,
+        Event::Eliminate(row) | Event::Place(row, _) => Some(*row),
+    };
+    let card = row.and_then(|row| history.replay(index).get(row)?.last().map(String::from));
+
+    match card {
+        Some(card) => format!("`{}` ({})", event, card),
+        None => format!("`{}`", event),
+    }
+}
+
+fn sorted(archive: &Archive) -> Vec<(&Uuid, &History)>
+{
+    let mut games: Vec<(&Uuid, &History)> = archive.iter().collect();
+    games.sort_by_key(|(id, history)| (*history.start_date(), **id));
+    games
+}
+
+fn duration(history: &History) -> Option<Duration>
+{
+    Some((*history.end_date())? - (*history.start_date())?)
+}
+
+/// Quotes `field` if it contains characters that have a meaning in CSV.
+fn csv_field(field: &str) -> String
+{
+    if field.contains(&[',', '"', '\n', '\r'][..]) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        String::from(field)
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::game::{
+        card::{
+            Card,
+            Rank,
+            Suit,
+        },
+        GameResult,
+    };
+
+    fn archive() -> Archive
+    {
+        let history = History::ended(
+            &[Event::Eliminate(1), Event::Place(0, 1)],
+            Some(GameResult::Lose),
+            12,
+        );
+
+        vec![(Uuid::nil(), history)].into_iter().collect()
+    }
+
+    #[test]
+    fn csv_has_one_row_per_game()
+    {
+        let csv = csv(&archive());
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines.len(), 2);
+        assert!(lines[1].starts_with("00000000-0000-0000-0000-000000000000,"));
+        assert!(lines[1].ends_with(",0,Lose,12,2,E2 P1-2"));
+    }
+
+    #[test]
+    fn csv_fields_are_quoted()
+    {
+        assert_eq!(csv_field("E2 P1-2"), "E2 P1-2");
+        assert_eq!(csv_field("a,\"b\""), "\"a,\"\"b\"\"\"");
+    }
+
+    #[test]
+    fn markdown_lists_moves()
+    {
+        let deal = Event::Deal([
+            Card::new(Suit::Spades, Rank::Ace),
+            Card::new(Suit::Hearts, Rank::Queen),
+            Card::new(Suit::Hearts, Rank::King),
+            Card::new(Suit::Clubs, Rank::Num(2)),
+        ]);
+        let history = History::ended(
+            &[deal, Event::Eliminate(1), Event::Place(0, 1)],
+            Some(GameResult::Lose),
+            12,
+        );
+        let markdown = markdown(&vec![(Uuid::nil(), history)].into_iter().collect());
+
+        assert!(markdown.contains("- Cards left: 12\n"));
+        assert!(markdown.contains("1. `D A<< Q<3 K<3 2cc`, "));
+        assert!(markdown.contains("2. `E2` (Q<3), "));
+        assert!(markdown.contains("3. `P1-2` (A<<), "));
+    }
+}
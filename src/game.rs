@@ -1,15 +1,23 @@
+pub mod analytics;
 pub mod card;
 mod game;
 mod history;
+mod strategy;
 
 pub use card::Deck;
 pub use game::{
+    DiscardPile,
     Game,
     GameError,
     GameResult,
+    Move,
     RowIndex,
+    Table,
+    Variant,
 };
 pub use history::{
     Event,
     History,
+    Outcome,
 };
+pub use strategy::Strategy;
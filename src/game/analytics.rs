@@ -0,0 +1,173 @@
+use std::iter::{
+    once,
+    zip,
+};
+
+use chrono::Duration;
+use getset::Getters;
+
+use crate::game::{
+    Event,
+    History,
+};
+
+/// Thinking time analytics of a recorded game.
+#[derive(Getters)]
+pub struct Timings
+{
+    #[getset(get = "pub")]
+    events: Vec<Event>,
+
+    /// The time spent before each event, either since the previous event or since the game started.
+    /// The time the application was suspended is left out.
+    #[getset(get = "pub")]
+    move_times: Vec<Duration>,
+}
+
+impl Timings
+{
+    /// Computes the timings of `history`. Histories recorded without timestamps have no timings.
+    pub fn new(history: &History) -> Option<Timings>
+    {
+        Timings::from_timestamps(
+            history.events(),
+            history.timestamps(),
+            history.suspensions(),
+        )
+    }
+
+    fn from_timestamps(
+        events: &[Event],
+        timestamps: &[i64],
+        suspensions: &[(i64, i64)],
+    ) -> Option<Timings>
+    {
+        if events.is_empty() || events.len() != timestamps.len() {
+            return None;
+        }
+
+        let move_times = zip(once(0).chain(timestamps.iter().copied()), timestamps.iter())
+            .map(|(previous, &current)| {
+                let suspended: i64 = suspensions
+                    .iter()
+                    .map(|&(start, end)| (end.min(current) - start.max(previous)).max(0))
+                    .sum();
+                (current - previous - suspended).max(0)
+            })
+            .map(Duration::milliseconds)
+            .collect();
+
+        Some(Timings {
+            events: events.to_vec(),
+            move_times,
+        })
+    }
+
+    /// Total time spent thinking during the game.
+    pub fn total(&self) -> Duration
+    {
+        self.move_times
+            .iter()
+            .fold(Duration::zero(), |total, &time| total + time)
+    }
+
+    /// The time spent on each deal, from the deal up to and including the next one. The last deal
+    /// lasts until the last event.
+    pub fn deal_times(&self) -> Vec<Duration>
+    {
+        let mut deal_times: Vec<Duration> = Vec::new();
+
+        for (event, &time) in zip(&self.events, &self.move_times) {
+            if let Some(deal_time) = deal_times.last_mut() {
+                *deal_time = *deal_time + time;
+            }
+            if matches!(event, Event::Deal(_)) {
+                deal_times.push(Duration::zero());
+            }
+        }
+        deal_times
+    }
+
+    /// The index of the events that were preceded by the `count` longest pauses, longest first.
+    pub fn longest_pauses(&self, count: usize) -> Vec<(usize, Duration)>
+    {
+        let mut pauses: Vec<(usize, Duration)> =
+            self.move_times.iter().copied().enumerate().collect();
+
+        pauses.sort_by(|(_, a), (_, b)| b.cmp(a));
+        pauses.truncate(count);
+        pauses
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn events(count: usize) -> Vec<Event>
+    {
+        (0..count).map(|_| Event::Eliminate(0)).collect()
+    }
+
+    #[test]
+    fn no_timings_without_timestamps()
+    {
+        assert!(Timings::from_timestamps(&events(3), &[], &[]).is_none());
+    }
+
+    #[test]
+    fn suspensions_are_excluded()
+    {
+        // The application was suspended for almost 15 minutes before the third move, while the
+        // fourth move took a long time to think about.
+        let timings = Timings::from_timestamps(
+            &events(4),
+            &[1000, 3000, 900_000, 1_025_000],
+            &[(3500, 890_000)],
+        )
+        .unwrap();
+
+        assert_eq!(
+            timings.move_times(),
+            &vec![
+                Duration::seconds(1),
+                Duration::seconds(2),
+                Duration::milliseconds(10_500),
+                Duration::seconds(125)
+            ]
+        );
+        assert_eq!(timings.total(), Duration::milliseconds(138_500));
+        assert_eq!(
+            timings.longest_pauses(2),
+            vec![
+                (3, Duration::seconds(125)),
+                (2, Duration::milliseconds(10_500))
+            ]
+        );
+    }
+
+    #[test]
+    fn deal_times_include_the_next_deal()
+    {
+        use crate::game::card::{
+            Card,
+            Rank,
+            Suit,
+        };
+
+        let deal = Event::Deal([
+            Card::new(Suit::Spades, Rank::Ace),
+            Card::new(Suit::Hearts, Rank::Ace),
+            Card::new(Suit::Clubs, Rank::Ace),
+            Card::new(Suit::Diamonds, Rank::Ace),
+        ]);
+        let events = vec![deal.clone(), Event::Eliminate(0), deal, Event::Eliminate(1)];
+        let timings = Timings::from_timestamps(&events, &[1000, 2000, 4000, 8000], &[]).unwrap();
+
+        assert_eq!(
+            timings.deal_times(),
+            vec![Duration::seconds(3), Duration::seconds(4)]
+        );
+    }
+}
@@ -14,6 +14,15 @@ pub enum Suit
     Spades,
 }
 
+impl Suit
+{
+    /// Every suit, in the order they are listed to the player.
+    pub fn all() -> [Suit; 4]
+    {
+        [Suit::Spades, Suit::Hearts, Suit::Clubs, Suit::Diamonds]
+    }
+}
+
 impl From<&Suit> for String
 {
     fn from(suit: &Suit) -> Self
@@ -80,6 +89,20 @@ impl Card
         Card { suit, rank }
     }
 
+    /// The card written out in words, e.g. 'Queen of Hearts' or '10 of Clubs'.
+    pub fn name(&self) -> String
+    {
+        let rank = match self.rank() {
+            Rank::Ace => String::from("Ace"),
+            Rank::Num(x) => x.to_string(),
+            Rank::Knight => String::from("Knight"),
+            Rank::Queen => String::from("Queen"),
+            Rank::King => String::from("King"),
+        };
+
+        format!("{} of {:?}", rank, self.suit())
+    }
+
     pub fn greater(&self, other: &Card) -> Option<bool>
     {
         (self.suit() == other.suit()).then(|| self.rank() > other.rank())
@@ -94,7 +117,7 @@ impl From<&Card> for String
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct Deck(Vec<Card>);
 
 impl Deck
@@ -167,6 +190,13 @@ impl Deck
         cards.pop()
     }
 
+    /// Puts `card` back on top of the deck, so that it is the next card to be drawn.
+    pub fn put_back(&mut self, card: Card)
+    {
+        let Deck(cards) = self;
+        cards.push(card);
+    }
+
     /// Amount of cards remaining in the deck.
     pub fn len(&self) -> usize
     {
@@ -234,6 +264,16 @@ mod tests
             .is_none());
     }
 
+    #[test]
+    fn names()
+    {
+        assert_eq!(
+            Card::new(Suit::Hearts, Rank::Queen).name(),
+            "Queen of Hearts"
+        );
+        assert_eq!(Card::new(Suit::Clubs, Rank::Num(10)).name(), "10 of Clubs");
+    }
+
     #[test]
     fn deck_has_52_unique_cards()
     {
@@ -7,7 +7,16 @@ use std::{
     },
 };
 
+use chrono::{
+    DateTime,
+    Duration,
+    Local,
+};
 use getset::Getters;
+use serde::{
+    Deserialize,
+    Serialize,
+};
 
 use crate::game::{
     card::{
@@ -34,25 +43,82 @@ pub enum GameError
     EliminateNoGreaterCard,
     PlaceFromSingleCardRow,
     PlaceToNonEmptyRow,
+    PlaceWithoutEmptyRow,
+    UndoWithoutMoves,
 }
 
 impl Display for GameError
 {
+    /// Writes the reason for the error in a way that can be shown to the player.
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
     {
-        write!(f, "{:?}", self)
+        use GameError::*;
+
+        match self {
+            DealFromDeckWithInsufficientCards => write!(f, "There are no cards left to deal."),
+            DealWithSameColeredCardsOnTable => write!(
+                f,
+                "Cards can not be dealt while two rows end with cards of the same suit."
+            ),
+            EliminateEmptyRow => write!(f, "There is no card to eliminate in that row."),
+            EliminateNoGreaterCard => write!(
+                f,
+                "Only a card with a greater card of the same suit on the table can be eliminated."
+            ),
+            PlaceFromSingleCardRow => {
+                write!(
+                    f,
+                    "Only cards from rows with two or more cards can be placed."
+                )
+            }
+            PlaceToNonEmptyRow => write!(f, "Cards can only be placed in empty rows."),
+            PlaceWithoutEmptyRow => write!(f, "There is no empty row to place a card in."),
+            UndoWithoutMoves => write!(f, "There is no move to undo."),
+        }
     }
 }
 
 impl Error for GameError {}
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub enum GameResult
 {
     Win,
     Lose,
 }
 
+/// A move the player can make.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Move
+{
+    Deal,
+    Eliminate(RowIndex),
+    Place(RowIndex, RowIndex),
+}
+
+impl Display for Move
+{
+    /// Describes the move with rows counted from 1.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self {
+            Move::Deal => write!(f, "Deal new cards"),
+            Move::Eliminate(index) => write!(f, "Eliminate the card in row {}", index + 1),
+            Move::Place(from, to) => {
+                write!(f, "Place the card in row {} in row {}", from + 1, to + 1)
+            }
+        }
+    }
+}
+
+/// The set of rules a game is played with.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Variant
+{
+    /// A single 52 card deck dealt onto four rows.
+    Standard,
+}
+
 #[derive(Getters)]
 pub struct Game
 {
@@ -62,7 +128,9 @@ pub struct Game
     #[getset(get = "pub")]
     table: Table,
 
+    #[getset(get = "pub")]
     discard_pile: DiscardPile,
+
     history: History,
 }
 
@@ -72,10 +140,10 @@ impl Game
     pub fn new(deck: Deck) -> Game
     {
         Game {
+            history: History::new(deck.clone(), Variant::Standard),
             deck,
             table: [Vec::new(), Vec::new(), Vec::new(), Vec::new()],
             discard_pile: DiscardPile::new(),
-            history: History::new(),
         }
     }
 
@@ -169,21 +237,46 @@ impl Game
         Ok(self.history.last().unwrap())
     }
 
-    /// Checks if it is possible to place a card from the row with index `from` to the row with
-    /// index `to`.
-    pub fn check_place(&self, from: RowIndex, to: RowIndex) -> Result<(), GameError>
+    /// Checks if there is any card that can be placed.
+    pub fn check_place_possible(&self) -> Result<(), GameError>
     {
         use GameError::{
             PlaceFromSingleCardRow,
-            PlaceToNonEmptyRow,
+            PlaceWithoutEmptyRow,
         };
 
         let table = &self.table;
 
+        if !table.iter().any(Vec::is_empty) {
+            return Err(PlaceWithoutEmptyRow);
+        }
+        if !table.iter().any(|row| row.len() > 1) {
+            return Err(PlaceFromSingleCardRow);
+        }
+
+        Ok(())
+    }
+
+    /// Checks if it is possible to place the card from the row with index `from` in some row.
+    pub fn check_place_from(&self, from: RowIndex) -> Result<(), GameError>
+    {
         // The row the card is moved from must contain at least two cards.
-        (table[from].len() > 1)
-            .then(|| ())
-            .ok_or(PlaceFromSingleCardRow)?;
+        if self.table[from].len() > 1 {
+            Ok(())
+        } else {
+            Err(GameError::PlaceFromSingleCardRow)
+        }
+    }
+
+    /// Checks if it is possible to place a card from the row with index `from` to the row with
+    /// index `to`.
+    pub fn check_place(&self, from: RowIndex, to: RowIndex) -> Result<(), GameError>
+    {
+        use GameError::PlaceToNonEmptyRow;
+
+        let table = &self.table;
+
+        self.check_place_from(from)?;
 
         // The row the card is placed at must be empty.
         table[to]
@@ -211,9 +304,105 @@ impl Game
         Ok(self.history.last().unwrap())
     }
 
+    /// Every move that is possible on the current table. Eliminations come first, then placements
+    /// and last dealing.
+    pub fn legal_moves(&self) -> Vec<Move>
+    {
+        let eliminations = (0..4)
+            .filter(|&index| self.check_eliminate(index).is_ok())
+            .map(Move::Eliminate);
+        let placements = (0..4)
+            .flat_map(|from| (0..4).map(move |to| (from, to)))
+            .filter(|&(from, to)| self.check_place(from, to).is_ok())
+            .map(|(from, to)| Move::Place(from, to));
+        let deal = self.check_deal().is_ok().then_some(Move::Deal);
+
+        eliminations.chain(placements).chain(deal).collect()
+    }
+
+    /// The row to eliminate a card from if eliminating is all that can be done and the order the
+    /// cards are eliminated in can not make a difference. That is the case if there is a single
+    /// elimination, or if no elimination empties a row and so allows a card to be placed in between.
+    pub fn forced_elimination(&self) -> Option<RowIndex>
+    {
+        let moves = self.legal_moves();
+        let rows: Vec<RowIndex> = moves
+            .iter()
+            .filter_map(|legal_move| match legal_move {
+                Move::Eliminate(index) => Some(*index),
+                _ => None,
+            })
+            .collect();
+
+        if rows.len() != moves.len() {
+            return None;
+        }
+        match rows[..] {
+            [index] => Some(index),
+            [index, ..] if rows.iter().all(|&row| self.table[row].len() > 1) => Some(index),
+            _ => None,
+        }
+    }
+
+    /// Takes back the last move and returns the event of it.
+    pub fn undo(&mut self) -> Result<Event, GameError>
+    {
+        let event = self.history.pop().ok_or(GameError::UndoWithoutMoves)?;
+        let table = &mut self.table;
+
+        match &event {
+            Event::Deal(_) => {
+                // Put the cards back in the reverse order they were drawn.
+                for row in table.iter_mut().rev() {
+                    self.deck.put_back(row.pop().unwrap());
+                }
+            }
+            Event::Eliminate(index) => {
+                table[*index].push(self.discard_pile.pop().unwrap());
+            }
+            Event::Place(from, to) => {
+                let card = table[*to].pop().unwrap();
+                table[*from].push(card);
+            }
+        }
+
+        Ok(event)
+    }
+
+    /// Records that the application was suspended from `start` until `end`, see `History::suspend`.
+    pub fn suspend(&mut self, start: DateTime<Local>, end: DateTime<Local>)
+    {
+        self.history.suspend(start, end);
+    }
+
+    /// Ends the game and returns its history.
+    ///
+    /// The result is recorded if the game was decided, an abandoned game is recorded without one.
     pub fn end(self) -> History
     {
-        self.history.end()
+        let result = self.game_result();
+        let cards_left = self.deck.len() + self.table.iter().map(Vec::len).sum::<usize>();
+
+        self.history.end(result, cards_left)
+    }
+
+    /// Time spent on the game so far, or up to the last move if the game is over.
+    pub fn elapsed(&self) -> Duration
+    {
+        match (self.game_result(), self.history.timestamps().last()) {
+            (Some(_), Some(&milliseconds)) => Duration::milliseconds(milliseconds),
+            _ => self
+                .history
+                .start_date()
+                .map(|start_date| Local::now() - start_date)
+                .unwrap_or_else(Duration::zero),
+        }
+    }
+
+    /// Whether a move has been made and the game is not over yet. Ending such a game abandons it.
+    pub fn is_undecided(&self) -> bool
+    {
+        !self.history.events().is_empty() && self.game_result().is_none()
     }
 
     pub fn game_result(&self) -> Option<GameResult>
@@ -287,4 +476,162 @@ mod tests
 
         assert_eq!(game.game_result(), Some(GameResult::Win));
     }
+
+    #[test]
+    fn legal_moves_after_first_deal()
+    {
+        let mut game = Game::new(Deck::new());
+
+        assert_eq!(game.legal_moves(), vec![Move::Deal]);
+
+        // The first four cards of an unshuffled deck are all diamonds with the king in row 1.
+        game.deal().unwrap();
+        assert_eq!(
+            game.legal_moves(),
+            vec![Move::Eliminate(1), Move::Eliminate(2), Move::Eliminate(3)]
+        );
+    }
+
+    #[test]
+    fn forced_elimination()
+    {
+        let mut game = Game::new(Deck::new());
+        assert_eq!(game.forced_elimination(), None);
+
+        // Every elimination after the first deal empties a row.
+        game.deal().unwrap();
+        assert_eq!(game.forced_elimination(), None);
+
+        game.table = [
+            vec![
+                Card::new(Suit::Spades, Rank::Ace),
+                Card::new(Suit::Hearts, Rank::Num(3)),
+            ],
+            vec![
+                Card::new(Suit::Spades, Rank::Num(2)),
+                Card::new(Suit::Hearts, Rank::Num(5)),
+            ],
+            vec![Card::new(Suit::Hearts, Rank::Num(9))],
+            vec![Card::new(Suit::Clubs, Rank::King)],
+        ];
+        assert_eq!(game.forced_elimination(), Some(0));
+
+        // Eliminating the five of hearts first would allow a placement.
+        game.table[1].remove(0);
+        assert_eq!(game.forced_elimination(), None);
+
+        // Unless it is the only elimination.
+        game.table[0].pop();
+        assert_eq!(game.forced_elimination(), Some(1));
+    }
+
+    #[test]
+    fn undo_restores_table_and_deck()
+    {
+        let mut game = Game::new(Deck::new());
+
+        game.deal().unwrap();
+        let table = game.table.clone();
+
+        game.eliminate(3).unwrap();
+        assert!(matches!(game.undo(), Ok(Event::Eliminate(3))));
+        assert_eq!(game.table, table);
+        assert!(game.discard_pile.is_empty());
+
+        assert!(matches!(game.undo(), Ok(Event::Deal(_))));
+        assert!(game.table.iter().all(Vec::is_empty));
+        assert_eq!(game.deck, Deck::new());
+    }
+
+    #[test]
+    fn undo_without_moves()
+    {
+        let mut game = Game::new(Deck::new());
+
+        game.deal().unwrap();
+        game.undo().unwrap();
+        assert!(matches!(game.undo(), Err(GameError::UndoWithoutMoves)));
+    }
+
+    #[test]
+    fn check_place_possible()
+    {
+        let mut game = Game::new(Deck::new());
+
+        game.deal().unwrap();
+        assert!(matches!(
+            game.check_place_possible(),
+            Err(GameError::PlaceWithoutEmptyRow)
+        ));
+
+        game.eliminate(3).unwrap();
+        assert!(matches!(
+            game.check_place_possible(),
+            Err(GameError::PlaceFromSingleCardRow)
+        ));
+
+        game.table[0].push(Card::new(Suit::Spades, Rank::Ace));
+        assert!(game.check_place_possible().is_ok());
+        assert!(game.check_place_from(0).is_ok());
+        assert!(matches!(
+            game.check_place_from(1),
+            Err(GameError::PlaceFromSingleCardRow)
+        ));
+    }
+
+    #[test]
+    fn end_records_result()
+    {
+        let mut game = Game::new(empty_deck());
+
+        game.table = [
+            vec![Card::new(Suit::Spades, Rank::King)],
+            vec![Card::new(Suit::Hearts, Rank::King)],
+            vec![Card::new(Suit::Clubs, Rank::King)],
+            vec![Card::new(Suit::Diamonds, Rank::King)],
+        ];
+
+        let history = game.end();
+        assert_eq!(history.result(), &Some(GameResult::Win));
+        assert_eq!(history.cards_left(), &Some(4));
+        assert!(history.end_date().is_some());
+    }
+
+    #[test]
+    fn is_undecided()
+    {
+        let mut game = Game::new(Deck::new());
+        assert!(!game.is_undecided());
+
+        game.deal().unwrap();
+        assert!(game.is_undecided());
+
+        // The deck is empty and only kings are left.
+        let mut game = Game::new(empty_deck());
+        game.table = [
+            vec![Card::new(Suit::Spades, Rank::King)],
+            vec![Card::new(Suit::Hearts, Rank::King)],
+            vec![Card::new(Suit::Clubs, Rank::King)],
+            vec![Card::new(Suit::Diamonds, Rank::King)],
+        ];
+        game.history.push(Event::Eliminate(0)).unwrap();
+        assert!(!game.is_undecided());
+    }
+
+    #[test]
+    fn end_records_abandoned_game()
+    {
+        let mut game = Game::new(Deck::new());
+        game.deal().unwrap();
+
+        let history = game.end();
+        assert_eq!(history.result(), &None);
+        assert_eq!(history.cards_left(), &Some(52));
+        assert_eq!(history.deck(), &Some(Deck::new()));
+        assert_eq!(history.variant(), &Some(Variant::Standard));
+        assert_eq!(
+            history.version().as_deref(),
+            Some(env!("CARGO_PKG_VERSION"))
+        );
+    }
 }
@@ -1,15 +1,32 @@
+use std::{
+    error::Error,
+    fmt::{
+        self,
+        Display,
+    },
+    iter::zip,
+};
+
 use chrono::prelude::*;
+use getset::Getters;
 use serde::{
     Deserialize,
     Serialize,
 };
 
 use crate::game::{
-    card::Card,
+    card::{
+        Card,
+        Deck,
+        Rank,
+    },
+    GameResult,
     RowIndex,
+    Table,
+    Variant,
 };
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub enum Event
 {
     Deal([Card; 4]),
@@ -17,45 +34,393 @@ pub enum Event
     Place(RowIndex, RowIndex),
 }
 
+impl Display for Event
+{
+    /// Writes the event in a short notation with rows counted from 1, e.g. 'D 5<3 Kcc 2<> Q<<',
+    /// 'E2' and 'P1-3'.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self {
+            Event::Deal(cards) => write!(
+                f,
+                "D {}",
+                cards.iter().map(String::from).collect::<Vec<_>>().join(" ")
+            ),
+            Event::Eliminate(index) => write!(f, "E{}", index + 1),
+            Event::Place(from, to) => write!(f, "P{}-{}", from + 1, to + 1),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct HistoryEndedError;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+/// Why a recorded game can not be replayed, e.g. because it was edited by hand.
+#[derive(Debug, Eq, PartialEq)]
+pub enum InvalidHistoryError
+{
+    /// The event at this index refers to a row that does not exist.
+    RowOutOfRange(usize),
+
+    /// There are timestamps, but not one for every event.
+    TimestampCount
+    {
+        events: usize, timestamps: usize
+    },
+}
+
+impl Display for InvalidHistoryError
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self {
+            InvalidHistoryError::RowOutOfRange(index) => {
+                write!(f, "move {} refers to a row that does not exist", index + 1)
+            }
+            InvalidHistoryError::TimestampCount { events, timestamps } => {
+                write!(f, "{} timestamps for {} moves", timestamps, events)
+            }
+        }
+    }
+}
+
+impl Error for InvalidHistoryError {}
+
+/// How a recorded game ended.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Outcome
+{
+    Win,
+    Lose,
+    Abandoned,
+}
+
+impl Display for Outcome
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        write!(f, "{:?}", self)
+    }
+}
+
+// NOTE:
+//   Every field added after `events` must have a default value so that archives written by older
+//   versions can still be read.
+#[derive(Debug, Serialize, Deserialize, Clone, Getters, PartialEq)]
 pub struct History
 {
+    #[getset(get = "pub")]
     start_date: Option<DateTime<Local>>,
+
+    #[getset(get = "pub")]
     end_date: Option<DateTime<Local>>,
+
+    #[getset(get = "pub")]
     events: Vec<Event>,
+
+    /// The result of the game, `None` if the game was abandoned before it was decided.
+    #[serde(default)]
+    #[getset(get = "pub")]
+    result: Option<GameResult>,
+
+    /// Amount of cards that were not eliminated, both on the table and in the deck.
+    #[serde(default)]
+    #[getset(get = "pub")]
+    cards_left: Option<usize>,
+
+    /// The deck in the order it was in before the first deal.
+    #[serde(default)]
+    #[getset(get = "pub")]
+    deck: Option<Deck>,
+
+    #[serde(default)]
+    #[getset(get = "pub")]
+    variant: Option<Variant>,
+
+    /// Version of the application that recorded the game.
+    #[serde(default)]
+    #[getset(get = "pub")]
+    version: Option<String>,
+
+    /// The time of each event in milliseconds since `start_date`.
+    #[serde(default)]
+    #[getset(get = "pub")]
+    timestamps: Vec<i64>,
+
+    /// The periods the application was suspended, as their start and end in milliseconds since
+    /// `start_date`.
+    #[serde(default)]
+    #[getset(get = "pub")]
+    suspensions: Vec<(i64, i64)>,
 }
 
 impl History
 {
-    pub fn new() -> History
+    pub fn new(deck: Deck, variant: Variant) -> History
     {
         History {
             start_date: Some(Local::now()),
             end_date: None,
             events: Vec::new(),
+            result: None,
+            cards_left: None,
+            deck: Some(deck),
+            variant: Some(variant),
+            version: Some(String::from(env!("CARGO_PKG_VERSION"))),
+            timestamps: Vec::new(),
+            suspensions: Vec::new(),
         }
     }
 
     pub fn push(&mut self, event: Event) -> Result<(), HistoryEndedError>
     {
         if self.end_date.is_none() {
+            self.timestamps.push(self.elapsed_milliseconds());
             Ok(self.events.push(event))
         } else {
             Err(HistoryEndedError)
         }
     }
 
-    pub fn end(mut self) -> History
+    /// Removes the last event, unless the history has ended.
+    pub fn pop(&mut self) -> Option<Event>
+    {
+        if self.end_date.is_some() {
+            return None;
+        }
+        self.timestamps.pop();
+        self.events.pop()
+    }
+
+    /// Records that the application was suspended from `start` until `end`, unless the history
+    /// has ended.
+    pub fn suspend(&mut self, start: DateTime<Local>, end: DateTime<Local>)
+    {
+        if let (Some(start_date), None) = (self.start_date, self.end_date) {
+            self.suspensions.push((
+                (start - start_date).num_milliseconds(),
+                (end - start_date).num_milliseconds(),
+            ));
+        }
+    }
+
+    pub fn end(mut self, result: Option<GameResult>, cards_left: usize) -> History
     {
         self.end_date = Some(Local::now());
+        self.result = result;
+        self.cards_left = Some(cards_left);
         self
     }
 
+    /// A game of an unshuffled deck with `events` that ended with `result`, for use in tests.
+    #[cfg(test)]
+    pub fn ended(events: &[Event], result: Option<GameResult>, cards_left: usize) -> History
+    {
+        let mut history = History::new(Deck::new(), Variant::Standard);
+        for event in events {
+            history.push(event.clone()).unwrap();
+        }
+        history.end(result, cards_left)
+    }
+
+    /// Checks that every event refers to existing rows and that there is a timestamp for every
+    /// event, unless the game was recorded without them.
+    pub fn validate(&self) -> Result<(), InvalidHistoryError>
+    {
+        let rows = Table::default().len();
+        let invalid = self.events.iter().position(|event| match event {
+            Event::Deal(_) => false,
+            Event::Eliminate(index) => *index >= rows,
+            Event::Place(from, to) => *from >= rows || *to >= rows,
+        });
+        if let Some(index) = invalid {
+            return Err(InvalidHistoryError::RowOutOfRange(index));
+        }
+
+        if !self.timestamps.is_empty() && self.timestamps.len() != self.events.len() {
+            return Err(InvalidHistoryError::TimestampCount {
+                events: self.events.len(),
+                timestamps: self.timestamps.len(),
+            });
+        }
+        Ok(())
+    }
+
     pub fn last(&self) -> Option<&Event>
     {
         self.events.last()
     }
+
+    /// How the game ended.
+    ///
+    /// Games recorded before results were stored are judged by replaying their events. Such a game
+    /// is counted as lost if the whole deck was dealt without winning and abandoned otherwise.
+    pub fn outcome(&self) -> Outcome
+    {
+        match (&self.version, self.result) {
+            (_, Some(GameResult::Win)) => Outcome::Win,
+            (_, Some(GameResult::Lose)) => Outcome::Lose,
+            (Some(_), None) => Outcome::Abandoned,
+            (None, None) => {
+                let table = self.replay(self.events.len());
+
+                if table
+                    .iter()
+                    .all(|row| row.len() == 1 && row[0].rank() == &Rank::King)
+                {
+                    Outcome::Win
+                } else if self.undealt_cards() == 0 {
+                    Outcome::Lose
+                } else {
+                    Outcome::Abandoned
+                }
+            }
+        }
+    }
+
+    /// Amount of cards that were not eliminated when the game ended, both on the table and in the
+    /// deck. Falls back on replaying the events for games recorded before it was stored.
+    pub fn final_cards_left(&self) -> usize
+    {
+        self.cards_left.unwrap_or_else(|| {
+            self.replay(self.events.len())
+                .iter()
+                .map(Vec::len)
+                .sum::<usize>()
+                + self.undealt_cards()
+        })
+    }
+
+    /// The table as it was after the first `count` events. Replaying stops early at an event that
+    /// refers to a row that does not exist, see `validate`.
+    pub fn replay(&self, count: usize) -> Table
+    {
+        let mut table = Table::default();
+
+        for event in self.events.iter().take(count) {
+            match event {
+                Event::Deal(cards) => {
+                    for (row, card) in zip(&mut table, cards) {
+                        row.push(card.clone());
+                    }
+                }
+                Event::Eliminate(index) => match table.get_mut(*index) {
+                    Some(row) => {
+                        row.pop();
+                    }
+                    None => break,
+                },
+                Event::Place(from, to) => {
+                    if *from >= table.len() || *to >= table.len() {
+                        break;
+                    }
+                    if let Some(card) = table[*from].pop() {
+                        table[*to].push(card);
+                    }
+                }
+            }
+        }
+        table
+    }
+
+    /// Amount of cards that were still in the deck after the last event.
+    fn undealt_cards(&self) -> usize
+    {
+        let deck_size = self
+            .deck
+            .as_ref()
+            .map(Deck::len)
+            .unwrap_or_else(|| Deck::new().len());
+        let dealt = self
+            .events
+            .iter()
+            .filter(|event| matches!(event, Event::Deal(_)))
+            .count()
+            * 4;
+
+        deck_size.saturating_sub(dealt)
+    }
+
+    fn elapsed_milliseconds(&self) -> i64
+    {
+        self.start_date
+            .map(|start_date| (Local::now() - start_date).num_milliseconds())
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    // A record written before results and metadata were stored.
+    static LEGACY_HISTORY: &str = r#"{
+        "start_date": "2021-10-01T12:00:00+02:00",
+        "end_date": "2021-10-01T12:05:00+02:00",
+        "events": [
+            {"Deal": [
+                {"suit": "Spades", "rank": "King"},
+                {"suit": "Spades", "rank": {"Num": 2}},
+                {"suit": "Clubs", "rank": "King"},
+                {"suit": "Diamonds", "rank": "Queen"}
+            ]},
+            {"Eliminate": 1}
+        ]
+    }"#;
+
+    #[test]
+    fn legacy_history_is_replayed()
+    {
+        let history: History = serde_json::from_str(LEGACY_HISTORY).unwrap();
+
+        assert_eq!(history.version(), &None);
+        assert!(history.timestamps().is_empty());
+        assert_eq!(history.replay(1).iter().map(Vec::len).sum::<usize>(), 4);
+        assert_eq!(history.replay(2)[1], vec![]);
+        assert_eq!(history.outcome(), Outcome::Abandoned);
+        assert_eq!(history.final_cards_left(), 51);
+    }
+
+    #[test]
+    fn corrupt_legacy_history_is_replayed_up_to_invalid_event()
+    {
+        let corrupt = LEGACY_HISTORY.replace(r#"{"Eliminate": 1}"#, r#"{"Eliminate": 7}"#);
+        let history: History = serde_json::from_str(&corrupt).unwrap();
+
+        assert_eq!(history.replay(2), history.replay(1));
+        assert_eq!(history.outcome(), Outcome::Abandoned);
+        assert_eq!(history.final_cards_left(), 52);
+
+        let corrupt = LEGACY_HISTORY.replace(r#"{"Eliminate": 1}"#, r#"{"Place": [0, 4]}"#);
+        let history: History = serde_json::from_str(&corrupt).unwrap();
+
+        assert_eq!(history.replay(2), history.replay(1));
+    }
+
+    #[test]
+    fn invalid_history_is_detected()
+    {
+        let history = History::ended(&[Event::Eliminate(0)], None, 52);
+        assert_eq!(history.validate(), Ok(()));
+
+        let history = History::ended(&[Event::Eliminate(0), Event::Place(1, 4)], None, 52);
+        assert_eq!(
+            history.validate(),
+            Err(InvalidHistoryError::RowOutOfRange(1))
+        );
+
+        let mut history = History::ended(&[Event::Eliminate(0)], None, 52);
+        history.timestamps.push(10);
+        assert_eq!(
+            history.validate(),
+            Err(InvalidHistoryError::TimestampCount {
+                events: 1,
+                timestamps: 2
+            })
+        );
+
+        let legacy: History = serde_json::from_str(LEGACY_HISTORY).unwrap();
+        assert_eq!(legacy.validate(), Ok(()));
+    }
 }
@@ -0,0 +1,122 @@
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::game::{
+    card::Rank,
+    Game,
+    Move,
+};
+
+/// A way of picking the next move, used when the computer plays a game.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Strategy
+{
+    /// Makes the first legal move, the same one a hint suggests.
+    Simple,
+
+    /// Eliminates whenever it can. Otherwise it moves kings to empty rows before any other card,
+    /// and of the other cards the one that covers the most cards. Dealing comes last.
+    Greedy,
+}
+
+impl Strategy
+{
+    /// The move to make on the table of `game`, if there is any.
+    pub fn choose(&self, game: &Game) -> Option<Move>
+    {
+        let moves = game.legal_moves();
+
+        match self {
+            Strategy::Simple => moves.first().copied(),
+            Strategy::Greedy => {
+                // The first of the best moves, so eliminations are made from left to right.
+                moves
+                    .iter()
+                    .copied()
+                    .rev()
+                    .max_by_key(|&legal_move| score(game, legal_move))
+            }
+        }
+    }
+}
+
+/// How much the greedy strategy wants to make `legal_move`. Higher is better.
+fn score(game: &Game, legal_move: Move) -> (u8, usize)
+{
+    match legal_move {
+        Move::Eliminate(_) => (3, 0),
+        Move::Place(from, _) => {
+            let row = &game.table()[from];
+            let king = row.last().is_some_and(|card| *card.rank() == Rank::King);
+
+            (if king { 2 } else { 1 }, row.len())
+        }
+        Move::Deal => (0, 0),
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::game::card::{
+        Card,
+        Deck,
+        Suit,
+    };
+
+    /// A game where row 2 is empty, row 4 ends with a king and rows 1 and 3 end with other cards.
+    fn game_with_empty_row() -> Game
+    {
+        let mut deck = Deck::new();
+        while deck.draw().is_some() {}
+
+        // The last card put back is dealt first.
+        for (suit, rank) in [
+            (Suit::Hearts, Rank::King),
+            (Suit::Clubs, Rank::Num(7)),
+            (Suit::Hearts, Rank::Num(6)),
+            (Suit::Diamonds, Rank::Num(8)),
+            (Suit::Spades, Rank::Num(2)),
+            (Suit::Clubs, Rank::Num(4)),
+            (Suit::Hearts, Rank::Num(3)),
+            (Suit::Diamonds, Rank::Num(5)),
+        ] {
+            deck.put_back(Card::new(suit, rank));
+        }
+
+        let mut game = Game::new(deck);
+        game.deal().unwrap();
+        game.deal().unwrap();
+        game.eliminate(1).unwrap();
+        game.eliminate(1).unwrap();
+        game
+    }
+
+    #[test]
+    fn simple_makes_first_legal_move()
+    {
+        let mut game = Game::new(Deck::new());
+        assert_eq!(Strategy::Simple.choose(&game), Some(Move::Deal));
+
+        game.deal().unwrap();
+        assert_eq!(Strategy::Simple.choose(&game), Some(Move::Eliminate(1)));
+
+        let game = game_with_empty_row();
+        assert_eq!(Strategy::Simple.choose(&game), Some(Move::Place(0, 1)));
+    }
+
+    #[test]
+    fn greedy_places_kings_first()
+    {
+        let mut game = Game::new(Deck::new());
+        game.deal().unwrap();
+        assert_eq!(Strategy::Greedy.choose(&game), Some(Move::Eliminate(1)));
+
+        let game = game_with_empty_row();
+        assert_eq!(Strategy::Greedy.choose(&game), Some(Move::Place(3, 1)));
+    }
+}
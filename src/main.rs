@@ -1,9 +1,19 @@
 #![feature(iter_zip)]
 
+mod archive;
+mod cli;
+mod config;
 mod controllers;
+mod export;
 mod game;
+mod statistics;
 mod views;
 
+use std::{
+    env,
+    process,
+};
+
 use cursive::{
     logger,
     Cursive,
@@ -13,11 +23,50 @@ use log::{
     Record,
 };
 
-use crate::controllers::ViewController;
+use crate::{
+    config::{
+        keymap,
+        settings,
+        theme,
+        Keymap,
+        Settings,
+    },
+    controllers::{
+        TextController,
+        ViewController,
+    },
+};
 
 pub fn main()
 {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let settings = Settings::load(settings::DEFAULT_PATH).unwrap_or_else(|error| {
+        eprintln!("{}: {}", settings::DEFAULT_PATH, error);
+        process::exit(1);
+    });
+
+    if !args.is_empty() {
+        if let Err(error) = cli::run(&args, settings.history_path()) {
+            eprintln!("{}", error);
+            process::exit(1);
+        }
+        return;
+    }
+
+    let keymap = Keymap::load(keymap::DEFAULT_PATH).unwrap_or_else(|error| {
+        eprintln!("{}: {}", keymap::DEFAULT_PATH, error);
+        process::exit(1);
+    });
+
+    let theme =
+        theme::load(theme::DEFAULT_PATH, settings.theme().as_deref()).unwrap_or_else(|error| {
+            eprintln!("{}: {}", theme::DEFAULT_PATH, error);
+            process::exit(1);
+        });
+
     let mut siv = cursive::default();
+    siv.set_theme(theme);
 
     logger::init();
     siv.add_global_callback('?', Cursive::toggle_debug_console);
@@ -29,6 +78,11 @@ pub fn main()
             .build(),
     );
 
-    let _view_controller = ViewController::new(&mut siv);
-    siv.run();
+    if settings.screen_reader() {
+        let _text_controller = TextController::new(&mut siv, &keymap, &settings);
+        siv.run();
+    } else {
+        let _view_controller = ViewController::new(&mut siv, &keymap, &settings);
+        siv.run();
+    }
 }
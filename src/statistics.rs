@@ -0,0 +1,238 @@
+use std::collections::BTreeMap;
+
+use chrono::Datelike;
+use getset::{
+    CopyGetters,
+    Getters,
+};
+
+use crate::game::{
+    History,
+    Outcome,
+};
+
+/// The results of the games started during one week.
+#[derive(Clone, Debug, Default, CopyGetters)]
+pub struct Trend
+{
+    #[getset(get_copy = "pub")]
+    games_played: usize,
+
+    #[getset(get_copy = "pub")]
+    wins: usize,
+
+    decided: usize,
+    cards_left: usize,
+}
+
+impl Trend
+{
+    pub fn win_rate(&self) -> Option<f64>
+    {
+        ratio(self.wins, self.games_played)
+    }
+
+    /// Average amount of cards left in the games that were played to the end.
+    pub fn average_cards_left(&self) -> Option<f64>
+    {
+        ratio(self.cards_left, self.decided)
+    }
+}
+
+/// Lifetime statistics of every game in an archive.
+///
+/// Games without a single move are not counted. Abandoned games count as played, but not won, and
+/// break the win streak.
+#[derive(Clone, Debug, Default, CopyGetters, Getters)]
+pub struct Statistics
+{
+    #[getset(get_copy = "pub")]
+    games_played: usize,
+
+    #[getset(get_copy = "pub")]
+    wins: usize,
+
+    #[getset(get_copy = "pub")]
+    losses: usize,
+
+    #[getset(get_copy = "pub")]
+    abandoned: usize,
+
+    #[getset(get_copy = "pub")]
+    current_streak: usize,
+
+    #[getset(get_copy = "pub")]
+    best_streak: usize,
+
+    /// The amount of decided games for each amount of cards left on the table when it ended.
+    #[getset(get = "pub")]
+    table_sizes: BTreeMap<usize, usize>,
+
+    /// Trends keyed by ISO year and week number.
+    #[getset(get = "pub")]
+    weeks: BTreeMap<(i32, u32), Trend>,
+
+    cards_left: usize,
+
+    /// The outcome of every game, oldest game first.
+    outcomes: Vec<Outcome>,
+}
+
+impl Statistics
+{
+    pub fn new<'a, I: IntoIterator<Item = &'a History>>(histories: I) -> Statistics
+    {
+        let mut histories: Vec<&History> = histories
+            .into_iter()
+            .filter(|history| !history.events().is_empty())
+            .collect();
+        histories.sort_by_key(|history| *history.start_date());
+
+        let mut statistics = Statistics::default();
+
+        for history in histories {
+            let outcome = history.outcome();
+
+            statistics.games_played += 1;
+            statistics.outcomes.push(outcome);
+            match outcome {
+                Outcome::Win => {
+                    statistics.wins += 1;
+                    statistics.current_streak += 1;
+                    statistics.best_streak = statistics.best_streak.max(statistics.current_streak);
+                }
+                Outcome::Lose => {
+                    statistics.losses += 1;
+                    statistics.current_streak = 0;
+                }
+                Outcome::Abandoned => {
+                    statistics.abandoned += 1;
+                    statistics.current_streak = 0;
+                }
+            }
+
+            // Every card has been dealt in a decided game so all cards left are on the table.
+            let cards_left = (outcome != Outcome::Abandoned).then(|| history.final_cards_left());
+            if let Some(cards_left) = cards_left {
+                statistics.cards_left += cards_left;
+                *statistics.table_sizes.entry(cards_left).or_insert(0) += 1;
+            }
+
+            if let Some(start_date) = history.start_date() {
+                let week = start_date.iso_week();
+                let trend = statistics
+                    .weeks
+                    .entry((week.year(), week.week()))
+                    .or_default();
+
+                trend.games_played += 1;
+                if outcome == Outcome::Win {
+                    trend.wins += 1;
+                }
+                if let Some(cards_left) = cards_left {
+                    trend.decided += 1;
+                    trend.cards_left += cards_left;
+                }
+            }
+        }
+
+        statistics
+    }
+
+    pub fn win_rate(&self) -> Option<f64>
+    {
+        ratio(self.wins, self.games_played)
+    }
+
+    /// Average amount of cards left in the games that were played to the end.
+    pub fn average_cards_left(&self) -> Option<f64>
+    {
+        ratio(self.cards_left, self.wins + self.losses)
+    }
+
+    /// The outcomes of the last `count` games, oldest game first.
+    pub fn recent(&self, count: usize) -> &[Outcome]
+    {
+        &self.outcomes[self.outcomes.len().saturating_sub(count)..]
+    }
+}
+
+/// Formats `rate` as a percentage, or '-' if there is no rate.
+pub fn format_rate(rate: Option<f64>) -> String
+{
+    rate.map(|rate| format!("{:.1}%", rate * 100.0))
+        .unwrap_or_else(|| String::from("-"))
+}
+
+/// Formats `average` with one decimal, or '-' if there is no average.
+pub fn format_average(average: Option<f64>) -> String
+{
+    average
+        .map(|average| format!("{:.1}", average))
+        .unwrap_or_else(|| String::from("-"))
+}
+
+fn ratio(numerator: usize, denominator: usize) -> Option<f64>
+{
+    (denominator > 0).then(|| numerator as f64 / denominator as f64)
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::game::{
+        Event,
+        GameResult,
+    };
+
+    fn history(result: Option<GameResult>, cards_left: usize) -> History
+    {
+        History::ended(&[Event::Eliminate(0)], result, cards_left)
+    }
+
+    #[test]
+    fn counts_results_and_streaks()
+    {
+        let histories = vec![
+            history(Some(GameResult::Win), 4),
+            history(Some(GameResult::Win), 4),
+            history(Some(GameResult::Lose), 10),
+            history(None, 30),
+            history(Some(GameResult::Win), 4),
+        ];
+        let statistics = Statistics::new(&histories);
+
+        assert_eq!(statistics.games_played(), 5);
+        assert_eq!(statistics.wins(), 3);
+        assert_eq!(statistics.losses(), 1);
+        assert_eq!(statistics.abandoned(), 1);
+        assert_eq!(statistics.current_streak(), 1);
+        assert_eq!(statistics.best_streak(), 2);
+        assert_eq!(statistics.win_rate(), Some(0.6));
+        assert_eq!(statistics.average_cards_left(), Some(5.5));
+        assert_eq!(
+            statistics.table_sizes(),
+            &vec![(4, 3), (10, 1)].into_iter().collect()
+        );
+        assert_eq!(
+            statistics.weeks().values().map(Trend::wins).sum::<usize>(),
+            3
+        );
+        assert_eq!(
+            statistics.recent(2),
+            &[Outcome::Abandoned, Outcome::Win][..]
+        );
+        assert_eq!(statistics.recent(10).len(), 5);
+    }
+
+    #[test]
+    fn skips_games_without_moves()
+    {
+        let histories = vec![History::ended(&[], None, 52)];
+        let statistics = Statistics::new(&histories);
+
+        assert_eq!(statistics.games_played(), 0);
+        assert_eq!(statistics.win_rate(), None);
+    }
+}
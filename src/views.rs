@@ -1,5 +1,11 @@
+mod discard_view;
 mod highlight_view;
 mod row_view;
 
+pub use discard_view::DiscardView;
 pub use highlight_view::HighlightView;
-pub use row_view::RowView;
+pub use row_view::{
+    Animation,
+    RowView,
+    CARDS_DIALOG,
+};
@@ -0,0 +1,212 @@
+use cursive::{
+    direction::Direction,
+    event::{
+        Event,
+        EventResult,
+        Key,
+        MouseButton,
+        MouseEvent,
+    },
+    theme::{
+        Color,
+        ColorStyle,
+    },
+    traits::With,
+    view::View,
+    Printer,
+    Vec2,
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::{
+    config::{
+        settings::SuitGlyphs,
+        theme,
+    },
+    game::card::{
+        Card,
+        Suit,
+    },
+};
+
+/// The amount of cards shown while the view is collapsed.
+static RECENT: usize = 5;
+
+/// Shows the eliminated cards, either the most recent ones on a single line or all of them grouped
+/// by suit. Selecting the view switches between the two.
+pub struct DiscardView
+{
+    cards: Vec<Card>,
+    expanded: bool,
+    suit_glyphs: SuitGlyphs,
+
+    /// The size the view was given at the last layout.
+    size: Vec2,
+}
+
+impl DiscardView
+{
+    pub fn new() -> DiscardView
+    {
+        DiscardView {
+            cards: Vec::new(),
+            expanded: false,
+            suit_glyphs: SuitGlyphs::Ascii,
+            size: Vec2::zero(),
+        }
+    }
+
+    pub fn with_suit_glyphs(self, suit_glyphs: SuitGlyphs) -> DiscardView
+    {
+        self.with(|view| view.suit_glyphs = suit_glyphs)
+    }
+
+    pub fn set_suit_glyphs(&mut self, suit_glyphs: SuitGlyphs)
+    {
+        self.suit_glyphs = suit_glyphs;
+    }
+
+    pub fn set_cards(&mut self, cards: Vec<Card>)
+    {
+        self.cards = cards;
+    }
+
+    fn label(&self) -> &'static str
+    {
+        if self.expanded {
+            "[-] Discards:"
+        } else {
+            "[+] Recent discards:"
+        }
+    }
+
+    /// The lines to draw after the label, each a list of cards.
+    fn lines(&self) -> Vec<Vec<&Card>>
+    {
+        if self.expanded {
+            Suit::all()
+                .iter()
+                .map(|suit| {
+                    let mut cards: Vec<&Card> = self
+                        .cards
+                        .iter()
+                        .filter(|card| card.suit() == suit)
+                        .collect();
+                    cards.sort_by_key(|card| card.rank());
+                    cards
+                })
+                .collect()
+        } else {
+            vec![self.cards.iter().rev().take(RECENT).collect()]
+        }
+    }
+
+    /// The width of `cards` when they are drawn separated by spaces.
+    fn cards_width(&self, cards: &[&Card]) -> usize
+    {
+        cards
+            .iter()
+            .map(|card| self.suit_glyphs.card(card).width() + 1)
+            .sum()
+    }
+
+    fn toggle(&mut self) -> EventResult
+    {
+        self.expanded = !self.expanded;
+        EventResult::Consumed(None)
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// View
+// -------------------------------------------------------------------------------------------------
+
+impl View for DiscardView
+{
+    fn draw(&self, printer: &Printer)
+    {
+        let label = self.label();
+        if printer.focused {
+            theme::with_selected(printer, Color::TerminalDefault, |printer| {
+                printer.print((0, 0), label)
+            });
+        } else {
+            printer.print((0, 0), label);
+        }
+
+        for (line, cards) in self.lines().into_iter().enumerate() {
+            // The collapsed view fits on the line of the label.
+            let (mut x, y) = if self.expanded {
+                let suit = &Suit::all()[line];
+                let color = theme::suit(&printer.theme.palette, suit);
+
+                printer.with_color(ColorStyle::front(color), |printer| {
+                    printer.print((0, line + 1), &self.suit_glyphs.suit(suit))
+                });
+                (4, line + 1)
+            } else {
+                (label.width() + 1, 0)
+            };
+
+            for card in cards {
+                let s = self.suit_glyphs.card(card);
+                let color = theme::suit(&printer.theme.palette, card.suit());
+
+                printer.with_color(ColorStyle::front(color), |printer| {
+                    printer.print((x, y), &s)
+                });
+                x += s.width() + 1;
+            }
+        }
+
+        if !self.expanded && self.cards.len() > RECENT {
+            let x = label.width() + 1 + self.cards_width(&self.lines()[0]);
+            printer.print((x, 0), "...");
+        }
+    }
+
+    fn take_focus(&mut self, _: Direction) -> bool
+    {
+        !self.cards.is_empty()
+    }
+
+    fn on_event(&mut self, event: Event) -> EventResult
+    {
+        match event {
+            Event::Key(Key::Enter) => self.toggle(),
+            Event::Mouse {
+                offset,
+                position,
+                event,
+            } if position.fits_in_rect(offset, self.size) => match event {
+                MouseEvent::Press(MouseButton::Left) => EventResult::Consumed(None),
+                MouseEvent::Release(MouseButton::Left) => self.toggle(),
+                _ => EventResult::Ignored,
+            },
+            _ => EventResult::Ignored,
+        }
+    }
+
+    fn layout(&mut self, size: Vec2)
+    {
+        self.size = size;
+    }
+
+    fn required_size(&mut self, _: Vec2) -> Vec2
+    {
+        let lines = self.lines();
+
+        if self.expanded {
+            let width = lines.iter().map(|cards| 4 + self.cards_width(cards)).max();
+            Vec2::new(
+                width.unwrap_or(0).max(self.label().width()),
+                1 + lines.len(),
+            )
+        } else {
+            Vec2::new(
+                self.label().width() + 1 + self.cards_width(&lines[0]) + 3,
+                1,
+            )
+        }
+    }
+}
@@ -1,8 +1,6 @@
 use cursive::{
     theme::{
-        BaseColor,
-        Color,
-        Palette,
+        Effect,
         PaletteColor,
         Theme,
     },
@@ -14,6 +12,8 @@ use cursive::{
     Printer,
 };
 
+use crate::config::theme;
+
 /// Wrapper that allows views to be highlighted.
 pub struct HighlightView<V: Sized + View>
 {
@@ -44,8 +44,12 @@ impl<V: Sized + View> ViewWrapper for HighlightView<V>
 
     fn wrap_draw(&self, printer: &Printer)
     {
-        if self.highlighted {
-            printer.with_theme(&theme(), |printer| {
+        if self.highlighted && theme::is_monochrome(&printer.theme.palette) {
+            printer.with_effect(Effect::Reverse, |printer| {
+                self.with_view(|view| view.draw(printer));
+            });
+        } else if self.highlighted {
+            printer.with_theme(&theme(printer.theme), |printer| {
                 self.with_view(|view| view.draw(printer));
             });
         } else {
@@ -54,14 +58,10 @@ impl<V: Sized + View> ViewWrapper for HighlightView<V>
     }
 }
 
-fn theme() -> Theme
+/// The active theme with the background of views replaced by the colour of selected widgets.
+fn theme(active: &Theme) -> Theme
 {
-    // TODO:
-    //   Should not override the current theme with 'default'.
-    let mut palatte = Palette::default();
-    palatte[PaletteColor::View] = Color::Light(BaseColor::Yellow);
-
-    let mut theme = Theme::default();
-    theme.palette = palatte;
+    let mut theme = active.clone();
+    theme.palette[PaletteColor::View] = theme::selected(&theme.palette);
     theme
 }
@@ -1,4 +1,10 @@
-use std::rc::Rc;
+use std::{
+    rc::Rc,
+    time::{
+        Duration,
+        Instant,
+    },
+};
 
 use cursive::{
     align::HAlign,
@@ -8,14 +14,26 @@ use cursive::{
         Event,
         EventResult,
         Key,
+        MouseButton,
+        MouseEvent,
     },
     theme::{
-        BaseColor,
         Color,
         ColorStyle,
+        Effect,
     },
     traits::With,
-    view::View,
+    utils::markup::StyledString,
+    view::{
+        Nameable,
+        Resizable,
+        Scrollable,
+        View,
+    },
+    views::{
+        Dialog,
+        TextView,
+    },
     Cursive,
     Printer,
     Vec2,
@@ -27,16 +45,80 @@ use getset::{
 use unicode_width::UnicodeWidthStr;
 
 use crate::{
-    controllers::Mode,
-    game::card::{
-        Card,
-        Suit,
+    config::{
+        settings::{
+            CardStyle,
+            SuitGlyphs,
+        },
+        theme,
     },
+    controllers::Mode,
+    game::card::Card,
 };
 
+/// The amount of lines of a bordered card that is not covered by another card.
+static FACE_HEIGHT: usize = 5;
+
+/// The time between the cards of a deal appearing.
+static DEAL_INTERVAL: Duration = Duration::from_millis(90);
+
+/// The time an eliminated card is shown in reverse video, and then normally, while it flashes.
+static FLASH_INTERVAL: Duration = Duration::from_millis(80);
+
+/// The amount of times an eliminated card is shown in reverse video.
+static FLASHES: u32 = 2;
+
+/// The time a placed card takes to move to the next row.
+static PLACE_STEP: Duration = Duration::from_millis(100);
+
+/// The name of the dialog that shows every card of a row.
+pub static CARDS_DIALOG: &str = "row-cards";
+
+/// A short animation of the bottom of a row, see `RowView::animate`.
+#[derive(Clone, Debug)]
+pub enum Animation
+{
+    /// The bottom card is dealt as card `order` of the deal and appears after the cards before it.
+    Deal
+    {
+        order: u32
+    },
+
+    /// The card that was eliminated from the bottom of the row flashes before it disappears.
+    Eliminate
+    {
+        card: Card
+    },
+
+    /// A card moves from the bottom of one row to the bottom of another, passing the rows in
+    /// between. Rows are counted from the one the card leaves: `column` is this row and `distance`
+    /// the row the card is placed in. The card leaves from line `from_y` and arrives at `to_y`.
+    Place
+    {
+        card: Card,
+        column: isize,
+        distance: isize,
+        from_y: usize,
+        to_y: usize,
+    },
+}
+
+impl Animation
+{
+    fn duration(&self) -> Duration
+    {
+        match self {
+            Animation::Deal { order } => DEAL_INTERVAL * *order,
+            Animation::Eliminate { .. } => FLASH_INTERVAL * FLASHES * 2,
+            Animation::Place { distance, .. } => PLACE_STEP * distance.unsigned_abs() as u32,
+        }
+    }
+}
+
 #[derive(Getters, Setters)]
 pub struct RowView
 {
+    #[getset(get = "pub")]
     cards: Vec<Card>,
 
     #[getset(set)]
@@ -44,6 +126,20 @@ pub struct RowView
 
     #[getset(get = "pub", set = "pub")]
     mode: Option<Mode>,
+
+    /// True while the left mouse button is held down over the target of the row.
+    pressed: bool,
+
+    /// The size the row was given at the last layout.
+    size: Vec2,
+
+    /// The animation that is shown and when it started.
+    animation: Option<(Animation, Instant)>,
+
+    card_style: CardStyle,
+
+    #[getset(set = "pub")]
+    suit_glyphs: SuitGlyphs,
 }
 
 impl RowView
@@ -54,9 +150,24 @@ impl RowView
             cards: Vec::new(),
             mode: None,
             on_action: None,
+            pressed: false,
+            size: Vec2::zero(),
+            animation: None,
+            card_style: CardStyle::Compact,
+            suit_glyphs: SuitGlyphs::Ascii,
         }
     }
 
+    pub fn with_card_style(self, card_style: CardStyle) -> RowView
+    {
+        self.with(|view| view.card_style = card_style)
+    }
+
+    pub fn with_suit_glyphs(self, suit_glyphs: SuitGlyphs) -> RowView
+    {
+        self.with(|view| view.suit_glyphs = suit_glyphs)
+    }
+
     pub fn with_on_action<F: Fn(&mut Cursive, Option<Mode>) + 'static>(self, lambda: F) -> RowView
     {
         self.with(|view| {
@@ -81,6 +192,199 @@ impl RowView
     pub fn clear(&mut self)
     {
         self.cards.clear();
+        self.animation = None;
+    }
+
+    pub fn set_cards(&mut self, cards: Vec<Card>)
+    {
+        self.cards = cards;
+        self.animation = None;
+    }
+
+    /// Shows `animation` from now on, replacing any animation that has not finished yet. The cards
+    /// of the row are expected to already be the ones after the move that is animated.
+    pub fn animate(&mut self, animation: Animation)
+    {
+        self.animation = Some((animation, Instant::now()));
+    }
+
+    pub fn is_animating(&self) -> bool
+    {
+        self.running().is_some()
+    }
+
+    /// The width the row needs for the cards in its style.
+    pub fn width(&self) -> usize
+    {
+        match self.card_style {
+            CardStyle::Compact => 6,
+            CardStyle::Bordered => 9,
+        }
+    }
+
+    /// The amount of lines a card takes up when it is not covered by another card.
+    fn card_height(&self) -> usize
+    {
+        match self.card_style {
+            CardStyle::Compact => 1,
+            CardStyle::Bordered => FACE_HEIGHT,
+        }
+    }
+
+    /// The animation that has not finished yet together with the time it has been shown.
+    fn running(&self) -> Option<(&Animation, Duration)>
+    {
+        self.animation
+            .as_ref()
+            .map(|(animation, start)| (animation, start.elapsed()))
+            .filter(|(animation, elapsed)| *elapsed < animation.duration())
+    }
+
+    /// The cards that are drawn in the row, which leaves out a card that is still on its way.
+    fn visible_cards(&self) -> &[Card]
+    {
+        let hidden = match self.running() {
+            Some((Animation::Deal { .. }, _)) => 1,
+            Some((
+                Animation::Place {
+                    column, distance, ..
+                },
+                _,
+            )) if column == distance => 1,
+            _ => 0,
+        };
+
+        &self.cards[..self.cards.len().saturating_sub(hidden)]
+    }
+
+    /// True if the row can be acted on in the current mode.
+    pub fn is_target(&self) -> bool
+    {
+        self.mode
+            .as_ref()
+            .map(|mode| match mode {
+                Mode::Eliminate => !self.cards.is_empty(),
+                Mode::PlaceFrom => self.cards.len() > 1,
+                Mode::PlaceTo => self.cards.is_empty(),
+            })
+            .unwrap_or(false)
+    }
+
+    /// True if `position`, relative to the row, is on the bottom card, or anywhere in the row if it
+    /// is empty.
+    fn is_on_target(&self, position: Vec2) -> bool
+    {
+        let top = self.line(self.cards.len().saturating_sub(1), self.size.y);
+
+        self.is_target()
+            && position.x < self.size.x
+            && position.y < self.size.y
+            && (self.cards.is_empty()
+                || (position.y >= top && position.y < top + self.card_height()))
+    }
+
+    /// True if `position`, relative to the row, is on the line that stands in for buried cards.
+    fn is_on_buried(&self, position: Vec2) -> bool
+    {
+        self.shows_buried(self.size.y) && position.x < self.size.x && position.y == 0
+    }
+
+    /// The amount of cards at the top of the row that are collapsed into a single line so the row
+    /// fits in `height` lines. The bottom card is always shown. If there is no room for both that
+    /// line and the bottom card, every other card is collapsed and the line is left out.
+    fn buried(&self, height: usize) -> usize
+    {
+        let len = self.cards.len();
+
+        if len == 0 || len - 1 + self.card_height() <= height {
+            0
+        } else if height <= self.card_height() {
+            len - 1
+        } else {
+            len - (height - self.card_height())
+        }
+    }
+
+    /// True if the first of `height` lines tells how many cards are buried.
+    fn shows_buried(&self, height: usize) -> bool
+    {
+        self.buried(height) > 0 && height > self.card_height()
+    }
+
+    /// The line the card at `index` is drawn on when the row is `height` lines high.
+    fn line(&self, index: usize, height: usize) -> usize
+    {
+        let label = usize::from(self.shows_buried(height));
+
+        (index + label).saturating_sub(self.buried(height))
+    }
+
+    /// Prints `card` on line `y`, with the background of selected cards if `selected` is set. Only
+    /// the top edge of a bordered card is printed unless `whole` is set.
+    fn print_card(&self, printer: &Printer, y: usize, card: &Card, selected: bool, whole: bool)
+    {
+        let color = theme::suit(&printer.theme.palette, card.suit());
+        let s = self.suit_glyphs.card(card);
+
+        let print = |printer: &Printer| match self.card_style {
+            CardStyle::Compact => {
+                let offset = HAlign::Center.get_offset(s.width(), printer.size.x);
+                printer.print((offset, y), &s);
+            }
+            CardStyle::Bordered => {
+                let inner = self.width() - 3;
+                let suit = self.suit_glyphs.suit(card.suit());
+
+                printer.print((0, y), &format!("┌{}{}┐", s, "─".repeat(inner - s.width())));
+                if whole {
+                    let blank = format!("│{}│", " ".repeat(inner));
+                    let center = HAlign::Center.get_offset(suit.width(), inner);
+
+                    printer.print((0, y + 1), &blank);
+                    printer.print(
+                        (0, y + 2),
+                        &format!(
+                            "│{}{}{}│",
+                            " ".repeat(center),
+                            suit,
+                            " ".repeat(inner - center - suit.width())
+                        ),
+                    );
+                    printer.print((0, y + 3), &blank);
+                    printer.print(
+                        (0, y + 4),
+                        &format!("└{}{}┘", "─".repeat(inner - s.width()), s),
+                    );
+                }
+            }
+        };
+
+        if selected {
+            theme::with_selected(printer, color, print);
+        } else {
+            printer.with_color(ColorStyle::front(color), print);
+        }
+    }
+
+    /// Shows every card of the row in a dialog if some of them are buried.
+    pub fn expand(&self) -> EventResult
+    {
+        if self.buried(self.size.y) == 0 {
+            return EventResult::Consumed(None);
+        }
+
+        let cards = self.cards.clone();
+        let suit_glyphs = self.suit_glyphs;
+        EventResult::with_cb(move |s| show_cards(s, &cards, suit_glyphs))
+    }
+
+    fn action(&self) -> EventResult
+    {
+        EventResult::Consumed(self.on_action.as_ref().map(|on_action| {
+            let mode = self.mode.clone();
+            let on_action = Rc::clone(on_action);
+            Callback::from_fn_once(move |s| on_action(s, mode))
+        }))
     }
 }
 
@@ -92,66 +396,249 @@ impl View for RowView
 {
     fn draw(&self, printer: &Printer)
     {
-        if self.cards.is_empty() {
+        let cards = self.visible_cards();
+        let buried = self.buried(printer.size.y);
+
+        if self.shows_buried(printer.size.y) {
+            let mut label = format!("+{} more", buried);
+            if label.width() >= printer.size.x {
+                label = format!("+{}", buried);
+            }
+            let offset = HAlign::Center.get_offset(label.width(), printer.size.x);
+
+            printer.with_color(ColorStyle::secondary(), |printer| {
+                printer.print((offset, 0), &label)
+            });
+        }
+
+        if cards.is_empty() {
             // Check if empty row should be focused.
             if printer.focused {
-                printer.with_color(
-                    ColorStyle::back(Color::Light(BaseColor::Yellow)),
-                    |printer| {
-                        printer.print((0, 0), "     ");
-                    },
-                );
+                theme::with_selected(printer, Color::TerminalDefault, |printer| {
+                    printer.print((0, 0), &" ".repeat(self.width() - 1));
+                });
             }
         } else {
             // Draw the cards in the row.
-            for (y, card) in self.cards.iter().enumerate() {
-                let color = match card.suit() {
-                    Suit::Hearts | Suit::Diamonds => Color::Dark(BaseColor::Red),
-                    Suit::Spades | Suit::Clubs => Color::Dark(BaseColor::Black),
-                };
-
-                let style = if printer.focused && self.cards.len() - 1 == y {
-                    ColorStyle::new(color, Color::Light(BaseColor::Yellow))
-                } else {
-                    ColorStyle::front(color)
-                };
+            for (index, card) in cards.iter().enumerate().skip(buried) {
+                let bottom = cards.len() - 1 == index;
+                self.print_card(
+                    printer,
+                    self.line(index, printer.size.y),
+                    card,
+                    bottom && (printer.focused || self.pressed),
+                    bottom,
+                );
+            }
+        }
 
-                let s = String::from(card);
-                let offset = HAlign::Center.get_offset(s.width(), printer.size.x);
+        match self.running() {
+            Some((Animation::Eliminate { card }, elapsed)) => {
+                let phase = elapsed.as_millis() / FLASH_INTERVAL.as_millis();
 
-                printer.with_color(style, |printer| printer.print((offset, y), &s));
+                if phase.is_multiple_of(2) {
+                    printer.with_effect(Effect::Reverse, |printer| {
+                        self.print_card(
+                            printer,
+                            self.line(self.cards.len(), printer.size.y),
+                            card,
+                            false,
+                            true,
+                        )
+                    });
+                } else {
+                    self.print_card(
+                        printer,
+                        self.line(self.cards.len(), printer.size.y),
+                        card,
+                        false,
+                        true,
+                    );
+                }
             }
+            Some((
+                animation @ Animation::Place {
+                    card,
+                    column,
+                    distance,
+                    from_y,
+                    to_y,
+                },
+                elapsed,
+            )) => {
+                let progress = elapsed.as_secs_f64() / animation.duration().as_secs_f64();
+
+                if (*distance as f64 * progress).round() as isize == *column {
+                    let y = *from_y as f64 + (*to_y as f64 - *from_y as f64) * progress;
+                    let line = self.line(y.round() as usize, printer.size.y);
+                    self.print_card(printer, line, card, false, true);
+                }
+            }
+            _ => (),
         }
     }
 
     fn take_focus(&mut self, _: Direction) -> bool
     {
-        self.mode
-            .as_ref()
-            .map(|mode| match mode {
-                Mode::Eliminate => !self.cards.is_empty(),
-                Mode::PlaceFrom => self.cards.len() > 1,
-                Mode::PlaceTo => self.cards.is_empty(),
-            })
-            .unwrap_or(false)
+        self.is_target()
     }
 
     fn on_event(&mut self, event: Event) -> EventResult
     {
         match event {
-            Event::Key(Key::Enter) => {
-                EventResult::Consumed(self.on_action.as_ref().map(|on_action| {
-                    let mode = self.mode.clone();
-                    let on_action = Rc::clone(&on_action);
-                    Callback::from_fn_once(move |s| on_action(s, mode))
-                }))
+            Event::Key(Key::Enter) => self.action(),
+
+            // NOTE:
+            //   Terminals only report the mouse moving while a button is held down, so pressing the
+            //   button is what highlights the row. The action is performed if the button is released
+            //   on the same row.
+            Event::Mouse {
+                offset,
+                position,
+                event,
+            } => {
+                let position = position.checked_sub(offset);
+                let on_target = position.is_some_and(|position| self.is_on_target(position));
+                let on_buried = position.is_some_and(|position| self.is_on_buried(position));
+
+                match event {
+                    MouseEvent::Press(MouseButton::Left) if on_buried => {
+                        EventResult::Consumed(None)
+                    }
+                    MouseEvent::Release(MouseButton::Left) if on_buried => self.expand(),
+                    MouseEvent::Press(MouseButton::Left) | MouseEvent::Hold(MouseButton::Left) => {
+                        self.pressed = on_target;
+                        if on_target {
+                            EventResult::Consumed(None)
+                        } else {
+                            EventResult::Ignored
+                        }
+                    }
+                    MouseEvent::Release(MouseButton::Left) if self.pressed => {
+                        self.pressed = false;
+                        if on_target {
+                            self.action()
+                        } else {
+                            EventResult::Consumed(None)
+                        }
+                    }
+                    _ => EventResult::Ignored,
+                }
             }
             _ => EventResult::Ignored,
         }
     }
 
-    fn required_size(&mut self, _: Vec2) -> Vec2
+    fn layout(&mut self, size: Vec2)
+    {
+        self.size = size;
+    }
+
+    fn required_size(&mut self, constraint: Vec2) -> Vec2
+    {
+        // Leave room for a card that is animated below the cards of the row.
+        let lines = match self.running() {
+            Some((Animation::Eliminate { .. }, _)) => self.cards.len() + 1,
+            Some((Animation::Place { from_y, to_y, .. }, _)) => {
+                self.cards.len().max(from_y.max(to_y) + 1)
+            }
+            _ => self.cards.len(),
+        };
+        let height = match lines {
+            0 => 0,
+            lines => lines - 1 + self.card_height(),
+        };
+
+        // Rows that do not fit are drawn with the cards at the top collapsed.
+        Vec2::from((self.width(), height.min(constraint.y)))
+    }
+}
+
+/// Shows every card of a row, including those that are buried.
+fn show_cards(s: &mut Cursive, cards: &[Card], suit_glyphs: SuitGlyphs)
+{
+    let palette = s.current_theme().palette.clone();
+
+    let mut text = StyledString::new();
+    for card in cards {
+        text.append_styled(
+            suit_glyphs.card(card),
+            ColorStyle::front(theme::suit(&palette, card.suit())),
+        );
+        text.append_plain("\n");
+    }
+
+    s.add_layer(
+        Dialog::around(TextView::new(text).scrollable().max_height(20))
+            .title("Row")
+            .dismiss_button("Close")
+            .with_name(CARDS_DIALOG),
+    );
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::game::card::{
+        Rank,
+        Suit,
+    };
+
+    fn row(card_style: CardStyle) -> RowView
+    {
+        let mut row = RowView::new().with_card_style(card_style);
+        row.set_cards(
+            (2..8)
+                .map(|n| Card::new(Suit::Spades, Rank::Num(n)))
+                .collect(),
+        );
+        row
+    }
+
+    fn bottom_end(row: &RowView, height: usize) -> usize
+    {
+        row.line(row.cards.len() - 1, height) + row.card_height()
+    }
+
+    fn check_collapsing(card_style: CardStyle)
     {
-        Vec2::from((6, self.cards.len()))
+        let row = row(card_style);
+        let fits = row.cards.len() - 1 + row.card_height();
+
+        assert_eq!(row.buried(fits), 0);
+        assert!(!row.shows_buried(fits));
+        assert_eq!(row.line(0, fits), 0);
+        assert_eq!(bottom_end(&row, fits), fits);
+
+        // One card more is buried than there are missing lines, to make room for the label.
+        assert_eq!(row.buried(fits - 1), 2);
+        assert!(row.shows_buried(fits - 1));
+        assert_eq!(row.line(2, fits - 1), 1);
+        assert_eq!(bottom_end(&row, fits - 1), fits - 1);
+
+        let short = row.card_height();
+        assert_eq!(row.buried(short), row.cards.len() - 1);
+        assert!(!row.shows_buried(short));
+        assert_eq!(bottom_end(&row, short), short);
+
+        assert_eq!(row.line(row.cards.len() - 1, 1), 0);
+    }
+
+    #[test]
+    fn compact_rows_collapse()
+    {
+        check_collapsing(CardStyle::Compact);
+    }
+
+    #[test]
+    fn bordered_rows_collapse()
+    {
+        check_collapsing(CardStyle::Bordered);
+
+        let row = row(CardStyle::Bordered);
+        assert_eq!(row.buried(FACE_HEIGHT + 1), row.cards.len() - 1);
+        assert!(row.shows_buried(FACE_HEIGHT + 1));
+        assert_eq!(bottom_end(&row, FACE_HEIGHT + 1), FACE_HEIGHT + 1);
     }
 }